@@ -1,22 +1,48 @@
-use std::env::var;
+use std::{env::var, fmt::Write, fs, path::Path};
 
 fn feature_enabled(name: &str) -> bool {
-    var(&format!("CARGO_FEATURE_{}", name.to_uppercase().replace("-", "_"))).is_ok()
+    var(format!("CARGO_FEATURE_{}", name.to_uppercase().replace("-", "_"))).is_ok()
 }
 
 fn emit_feature(name: &str) {
     println!("cargo:rustc-cfg=feature=\"{}\"", name);
 }
 
+/// Convert `src/pnp.ids` into a table sorted by ID for `src/pnp.rs`.
+fn generate_pnp_ids() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/pnp.ids");
+
+    let ids = fs::read_to_string("src/pnp.ids").expect("src/pnp.ids");
+    let mut entries: Vec<_> = ids
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.split_once('\t').expect("pnp.ids entries are tab-separated"))
+        .collect();
+    entries.sort_by_key(|&(id, _)| id);
+    entries.dedup_by_key(|&mut (id, _)| id);
+
+    let mut table = String::from("const PNP_IDS: &[(&str, &str)] = &[\n");
+    for (id, name) in entries {
+        writeln!(table, "    ({:?}, {:?}),", id, name.trim()).unwrap();
+    }
+    table.push_str("];\n");
+
+    let out = var("OUT_DIR").expect("OUT_DIR");
+    fs::write(Path::new(&out).join("pnp_ids.rs"), table).expect("pnp_ids.rs");
+}
+
 fn main() {
+    println!(
+        "cargo:rustc-check-cfg=cfg(feature, values(\"has-ddc-i2c\", \"has-ddc-winapi\", \"has-nvapi\", \"has-ddc-macos\"))"
+    );
+
     if var("CARGO_CFG_TARGET_OS") == Ok("macos".into()) {
         if feature_enabled("ddc-macos") {
             emit_feature("has-ddc-macos");
         }
-    } else if var("CARGO_CFG_UNIX").is_ok() {
-        if feature_enabled("ddc-i2c") {
-            emit_feature("has-ddc-i2c");
-        }
+    } else if var("CARGO_CFG_UNIX").is_ok() && feature_enabled("ddc-i2c") {
+        emit_feature("has-ddc-i2c");
     }
 
     if var("CARGO_CFG_WINDOWS").is_ok() {
@@ -27,4 +53,8 @@ fn main() {
             emit_feature("has-nvapi");
         }
     }
+
+    if feature_enabled("pnp-ids") {
+        generate_pnp_ids();
+    }
 }
@@ -0,0 +1,164 @@
+//! Adjust the luminance of a display to match the ambient light.
+//!
+//! The application supplies illuminance readings from a light sensor, and a
+//! `BrightnessController` maps them to luminance through a curve and paces
+//! the writes so the display isn't flooded with commands.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use {
+//!     ddc_hi::{ambient::BrightnessController, Display},
+//!     std::time::Duration,
+//! };
+//!
+//! # fn read_sensor() -> Option<f64> { None }
+//! let display = Display::enumerate().into_iter().next().unwrap();
+//! let mut controller = BrightnessController::new(display)
+//!     .curve(vec![(0.0, 10), (50.0, 40), (500.0, 100)])
+//!     .hysteresis(5);
+//! controller.run(read_sensor, Duration::from_secs(1));
+//! ```
+
+use {
+    crate::{Ddc, Display, Error, FeatureCode},
+    log::{debug, trace, warn},
+    std::{
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+/// The luminance feature.
+const LUMINANCE: FeatureCode = 0x10;
+
+/// Maps ambient light readings to luminance writes on a display.
+///
+/// The curve maps illuminance in lux to a luminance percentage, linearly
+/// interpolating between its points. A new luminance is only written once it
+/// differs from the current one by at least the hysteresis, and no sooner
+/// than the minimum interval after the previous write.
+pub struct BrightnessController {
+    display: Display,
+    curve: Vec<(f64, u8)>,
+    hysteresis: u8,
+    min_interval: Duration,
+    maximum: Option<u16>,
+    current: Option<u8>,
+    last_write: Option<Instant>,
+}
+
+impl BrightnessController {
+    /// Control the luminance of `display`.
+    ///
+    /// The default curve goes from 10% in the dark to 100% at 1000 lux, with
+    /// a hysteresis of 3% and at most one write every two seconds.
+    pub fn new(display: Display) -> Self {
+        BrightnessController {
+            display,
+            curve: vec![(0.0, 10), (1000.0, 100)],
+            hysteresis: 3,
+            min_interval: Duration::from_secs(2),
+            maximum: None,
+            current: None,
+            last_write: None,
+        }
+    }
+
+    /// The points of the curve, as illuminance in lux and luminance in
+    /// percent.
+    pub fn curve(mut self, mut points: Vec<(f64, u8)>) -> Self {
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+        self.curve = points;
+        self
+    }
+
+    /// How many percent the luminance must change by before it is written.
+    pub fn hysteresis(mut self, percent: u8) -> Self {
+        self.hysteresis = percent;
+        self
+    }
+
+    /// The minimum time between writes.
+    pub fn min_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = interval;
+        self
+    }
+
+    /// The controlled display.
+    pub fn display(&self) -> &Display {
+        &self.display
+    }
+
+    /// Stop controlling the display and return it.
+    pub fn into_display(self) -> Display {
+        self.display
+    }
+
+    /// The luminance percentage that the curve maps an illuminance to.
+    pub fn target(&self, lux: f64) -> u8 {
+        let upper = self.curve.iter().position(|&(x, _)| x > lux);
+        match upper {
+            None => self.curve.last().map(|&(_, y)| y).unwrap_or(100),
+            Some(0) => self.curve[0].1,
+            Some(i) => {
+                let ((x0, y0), (x1, y1)) = (self.curve[i - 1], self.curve[i]);
+                let t = (lux - x0) / (x1 - x0);
+                (y0 as f64 + t * (y1 as f64 - y0 as f64)).round() as u8
+            },
+        }
+    }
+
+    /// Handle an illuminance reading, writing the luminance it maps to if
+    /// it should be changed.
+    ///
+    /// Returns the percentage written, if any.
+    pub fn update(&mut self, lux: f64) -> Result<Option<u8>, Error> {
+        trace!("BrightnessController::update({})", lux);
+
+        let maximum = match self.maximum {
+            Some(maximum) => maximum,
+            None => {
+                let value = self.display.get_vcp_feature(LUMINANCE)?;
+                self.current = Some((value.value() as u32 * 100 / value.maximum().max(1) as u32) as u8);
+                self.maximum = Some(value.maximum());
+                value.maximum()
+            },
+        };
+
+        let target = self.target(lux).min(100);
+        if self
+            .current
+            .is_some_and(|current| current.abs_diff(target) < self.hysteresis.max(1))
+        {
+            return Ok(None)
+        }
+        if self.last_write.is_some_and(|last| last.elapsed() < self.min_interval) {
+            return Ok(None)
+        }
+
+        debug!(
+            "Setting luminance of {} to {}% for {} lux",
+            self.display.info, target, lux
+        );
+        let value = (target as u32 * maximum as u32 / 100) as u16;
+        self.display.set_vcp_feature(LUMINANCE, value)?;
+        self.current = Some(target);
+        self.last_write = Some(Instant::now());
+
+        Ok(Some(target))
+    }
+
+    /// Poll `sensor` every `interval` and handle its readings, until it
+    /// returns `None`.
+    ///
+    /// Failed writes are logged and retried with the next reading.
+    pub fn run<F: FnMut() -> Option<f64>>(&mut self, mut sensor: F, interval: Duration) {
+        while let Some(lux) = sensor() {
+            if let Err(e) = self.update(lux) {
+                warn!("Failed to adjust luminance of {}: {}", self.display.info, e);
+            }
+            thread::sleep(interval);
+        }
+    }
+}
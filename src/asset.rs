@@ -0,0 +1,92 @@
+use {
+    crate::{Display, Error, FeatureCode},
+    log::trace,
+};
+
+/// The maximum length of an asset tag written by `Display::set_asset_tag`.
+pub const ASSET_TAG_MAX_LEN: usize = 64;
+
+/// Decode text up to the first NUL, replacing any non-ASCII bytes.
+fn decode_text(table: &[u8]) -> String {
+    let len = table.iter().position(|&b| b == 0).unwrap_or(table.len());
+    table[..len]
+        .iter()
+        .map(|&b| {
+            if b.is_ascii() {
+                b as char
+            } else {
+                char::REPLACEMENT_CHARACTER
+            }
+        })
+        .collect()
+}
+
+/// Encode printable ASCII text, NUL-terminated if it is shorter than
+/// `max_len`.
+fn encode_text(what: &str, text: &str, max_len: usize) -> Result<Vec<u8>, Error> {
+    if !text.bytes().all(|b| b.is_ascii_graphic() || b == b' ') {
+        return Err(Error::InvalidArgument(format!(
+            "{} {:?} is not printable ASCII",
+            what, text
+        )))
+    }
+    if text.len() > max_len {
+        return Err(Error::InvalidArgument(format!(
+            "{} is {} bytes, the maximum is {}",
+            what,
+            text.len(),
+            max_len
+        )))
+    }
+
+    let mut table = text.as_bytes().to_owned();
+    if table.len() < max_len {
+        table.push(0);
+    }
+    Ok(table)
+}
+
+impl Display {
+    /// Read the asset tag (`0xd2`) assigned to the display.
+    ///
+    /// Trailing NUL padding is removed, and any non-ASCII bytes are replaced.
+    pub fn asset_tag(&mut self) -> Result<String, Error> {
+        trace!("Display::asset_tag");
+
+        self.read_table(0xd2, None).map(|table| decode_text(&table))
+    }
+
+    /// Assign an asset tag (`0xd2`) to the display.
+    ///
+    /// The tag must be printable ASCII of at most `ASSET_TAG_MAX_LEN` bytes.
+    /// Shorter tags are NUL-terminated so that no part of a previous, longer
+    /// tag remains.
+    pub fn set_asset_tag(&mut self, tag: &str) -> Result<(), Error> {
+        trace!("Display::set_asset_tag({:?})", tag);
+
+        let table = encode_text("asset tag", tag, ASSET_TAG_MAX_LEN)?;
+        self.write_table(0xd2, &table, None)
+    }
+
+    /// Read a user string shown in the display's on-screen menu, stored in
+    /// the table feature `code`.
+    ///
+    /// MCCS doesn't assign a code to user strings, so it must come from the
+    /// manufacturer's documentation. The text is decoded as with `asset_tag`.
+    pub fn user_string(&mut self, code: FeatureCode) -> Result<String, Error> {
+        trace!("Display::user_string(0x{:02x})", code);
+
+        self.read_table(code, None).map(|table| decode_text(&table))
+    }
+
+    /// Write a user string to the table feature `code`, which holds at most
+    /// `max_len` bytes.
+    ///
+    /// The text is validated and terminated as with `set_asset_tag`.
+    pub fn set_user_string(&mut self, code: FeatureCode, text: &str, max_len: usize) -> Result<(), Error> {
+        trace!("Display::set_user_string(0x{:02x}, {:?}, {})", code, text, max_len);
+
+        let table = encode_text("user string", text, max_len)?;
+        self.write_table(code, &table, None)
+    }
+}
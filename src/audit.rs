@@ -0,0 +1,79 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{Ddc, Display, Error, FeatureCode},
+    log::trace,
+};
+
+/// A discrepancy between a display's capabilities and its behaviour, as
+/// found by `Display::audit`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AuditFinding {
+    /// The VCP code of the advertised feature.
+    pub code: FeatureCode,
+    /// What was wrong with it.
+    pub issue: AuditIssue,
+}
+
+/// The kinds of discrepancies reported by `Display::audit`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AuditIssue {
+    /// The feature is advertised but could not be read.
+    Refused(String),
+    /// The current value of a continuous feature exceeds its maximum.
+    AboveMaximum {
+        /// The current value.
+        value: u16,
+        /// The maximum value reported alongside it.
+        maximum: u16,
+    },
+    /// The current value is not one of the values advertised for the
+    /// feature.
+    UnlistedValue(u8),
+}
+
+impl Display {
+    /// Compare the features advertised by the capabilities string against
+    /// what the display actually reports.
+    ///
+    /// Every readable advertised feature is read once. An empty result means
+    /// the display behaved as advertised.
+    pub fn audit(&mut self) -> Result<Vec<AuditFinding>, Error> {
+        trace!("Display::audit");
+
+        let raw = self.capabilities_string()?;
+        let caps = mccs_caps::parse_capabilities(&raw).map_err(|e| self.context(Error::CapabilitiesParseError(e)))?;
+        self.fill_capabilities(&caps);
+
+        let mut findings = Vec::new();
+        for (&code, desc) in &caps.vcp_features {
+            if !self.info.is_readable(code) {
+                continue
+            }
+
+            let issue = match self.get_vcp_feature(code) {
+                Err(e) => Some(AuditIssue::Refused(e.inner().to_string())),
+                Ok(value) if !desc.values.is_empty() =>
+                    Some(AuditIssue::UnlistedValue(value.sl)).filter(|_| !desc.values.contains_key(&value.sl)),
+                Ok(value) if self.is_continuous(code) && value.value() > value.maximum() =>
+                    Some(AuditIssue::AboveMaximum {
+                        value: value.value(),
+                        maximum: value.maximum(),
+                    }),
+                Ok(_) => None,
+            };
+            findings.extend(issue.map(|issue| AuditFinding { code, issue }));
+        }
+
+        Ok(findings)
+    }
+
+    fn is_continuous(&self, code: FeatureCode) -> bool {
+        matches!(
+            self.info.mccs_database.get(code).map(|desc| &desc.ty),
+            Some(mccs_db::ValueType::Continuous { .. })
+        )
+    }
+}
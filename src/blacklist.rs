@@ -0,0 +1,94 @@
+use {
+    crate::{Display, Error, ErrorKind, FeatureCode, Operation},
+    log::{debug, trace},
+    std::collections::BTreeMap,
+};
+
+/// Operations that keep failing on a display, so that they can be refused
+/// without waiting for the display to time out again.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Blacklist {
+    limit: Option<u32>,
+    failures: BTreeMap<(Operation, Option<FeatureCode>), u32>,
+}
+
+impl Blacklist {
+    fn is_blacklisted(&self, key: &(Operation, Option<FeatureCode>)) -> bool {
+        match self.limit {
+            Some(limit) => self.failures.get(key).is_some_and(|&failures| failures >= limit),
+            None => false,
+        }
+    }
+
+    /// Refuse an operation that has failed too many times in a row.
+    pub(crate) fn check(&self, op: Operation, code: Option<FeatureCode>) -> Result<(), Error> {
+        match self.is_blacklisted(&(op, code)) {
+            true => Err(Error::UnsupportedOp),
+            false => Ok(()),
+        }
+    }
+
+    /// Count a failure of an operation, or forget its failures once it
+    /// succeeds.
+    pub(crate) fn record<T>(&mut self, op: Operation, code: Option<FeatureCode>, res: &Result<T, Error>) {
+        let limit = match self.limit {
+            Some(limit) => limit,
+            None => return,
+        };
+
+        match res {
+            Ok(_) => {
+                self.failures.remove(&(op, code));
+            },
+            // only failures that suggest the display can't do it count
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    ErrorKind::Nak
+                        | ErrorKind::Checksum
+                        | ErrorKind::Timeout
+                        | ErrorKind::InvalidResponse
+                        | ErrorKind::UnsupportedFeature
+                        | ErrorKind::Other
+                ) =>
+            {
+                let failures = self.failures.entry((op, code)).or_default();
+                *failures += 1;
+                if *failures == limit {
+                    debug!("Giving up on {:?} {:?} after {} failures: {}", op, code, limit, e);
+                }
+            },
+            Err(_) => (),
+        }
+    }
+}
+
+impl<H> Display<H> {
+    /// Refuse operations that have failed `limit` times in a row with
+    /// `Error::UnsupportedOp`, for the rest of the session.
+    ///
+    /// Operations on individual features are counted separately. This keeps
+    /// applications that poll a partially working display responsive, rather
+    /// than waiting for it to time out every time. Disabled by default.
+    pub fn set_failure_limit(&mut self, limit: Option<u32>) {
+        self.blacklist.limit = limit;
+    }
+
+    /// The operations, and the features they apply to, that are refused
+    /// because they failed too many times.
+    pub fn blacklisted(&self) -> Vec<(Operation, Option<FeatureCode>)> {
+        self.blacklist
+            .failures
+            .keys()
+            .filter(|key| self.blacklist.is_blacklisted(key))
+            .copied()
+            .collect()
+    }
+
+    /// Forget the failures counted so far, allowing every operation again.
+    pub fn clear_blacklist(&mut self) {
+        trace!("Display::clear_blacklist");
+
+        self.blacklist.failures.clear();
+    }
+}
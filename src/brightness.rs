@@ -0,0 +1,236 @@
+//! A single brightness setting for every display, including the internal
+//! panels of laptops.
+//!
+//! External displays are adjusted through their luminance (`0x10`) and
+//! internal panels through the backlight, all on a scale of 0 to 100
+//! regardless of each display's own range. Displays that can't report their
+//! luminance are left out.
+//!
+//! Internal panels are only supported on Linux, through the sysfs backlight
+//! class. Windows exposes them through WMI instead, which is not implemented,
+//! so there and on other platforms only external displays are adjusted.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use ddc_hi::brightness::Brightness;
+//!
+//! let mut brightness = Brightness::new();
+//! println!("brightness is {:?}%", brightness.get());
+//! let report = brightness.set(70);
+//! for (info, error) in report.failures() {
+//!     eprintln!("{}: {}", info, error);
+//! }
+//! ```
+
+use {
+    crate::{broadcast::BroadcastReport, Ddc, Display, DisplayInfo, Error, FeatureCode},
+    log::{debug, trace},
+};
+#[cfg(target_os = "linux")]
+use {
+    crate::{Backend, BackendError},
+    std::{fs, io, path::PathBuf},
+};
+
+/// The luminance feature.
+const LUMINANCE: FeatureCode = 0x10;
+
+/// Where Linux exposes backlight devices.
+#[cfg(target_os = "linux")]
+const BACKLIGHT_CLASS: &str = "/sys/class/backlight";
+
+/// The backend reported for backlight devices.
+#[cfg(target_os = "linux")]
+pub const BACKLIGHT: Backend = Backend::Other("backlight");
+
+enum Output {
+    Ddc {
+        display: Box<Display>,
+        maximum: u16,
+    },
+    #[cfg(target_os = "linux")]
+    Backlight(Box<Backlight>),
+}
+
+impl Output {
+    fn info(&self) -> &DisplayInfo {
+        match *self {
+            Output::Ddc { ref display, .. } => &display.info,
+            #[cfg(target_os = "linux")]
+            Output::Backlight(ref backlight) => &backlight.info,
+        }
+    }
+
+    fn get(&mut self) -> Result<u8, Error> {
+        let (value, maximum) = match *self {
+            Output::Ddc { ref mut display, .. } => {
+                let value = display.get_vcp_feature(LUMINANCE)?;
+                (value.value() as u32, value.maximum() as u32)
+            },
+            #[cfg(target_os = "linux")]
+            Output::Backlight(ref backlight) => (backlight.read()?, backlight.maximum),
+        };
+
+        Ok((value * 100 / maximum.max(1)).min(100) as u8)
+    }
+
+    fn set(&mut self, percent: u8) -> Result<(), Error> {
+        let percent = percent.min(100) as u32;
+        match *self {
+            Output::Ddc {
+                ref mut display,
+                maximum,
+            } => display.set_vcp_feature(LUMINANCE, (percent * maximum as u32 / 100) as u16),
+            #[cfg(target_os = "linux")]
+            Output::Backlight(ref backlight) => backlight.write(percent * backlight.maximum / 100),
+        }
+    }
+}
+
+/// A Linux backlight device, such as that of a laptop panel.
+#[cfg(target_os = "linux")]
+struct Backlight {
+    path: PathBuf,
+    maximum: u32,
+    info: DisplayInfo,
+}
+
+#[cfg(target_os = "linux")]
+impl Backlight {
+    fn enumerate() -> Vec<Self> {
+        fs::read_dir(BACKLIGHT_CLASS)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                let maximum = fs::read_to_string(path.join("max_brightness"))
+                    .ok()?
+                    .trim()
+                    .parse()
+                    .ok()?;
+                let mut info = DisplayInfo::new(BACKLIGHT, entry.file_name().to_string_lossy().into_owned());
+                info.is_internal = Some(true);
+                Some(Backlight { path, maximum, info })
+            })
+            .collect()
+    }
+
+    fn error(&self, e: io::Error) -> Error {
+        match e.kind() {
+            io::ErrorKind::PermissionDenied =>
+                Error::permission_denied(BACKLIGHT, self.path.join("brightness").display().to_string()),
+            _ => BackendError::BacklightError(e).into(),
+        }
+    }
+
+    fn read(&self) -> Result<u32, Error> {
+        let value = fs::read_to_string(self.path.join("brightness")).map_err(|e| self.error(e))?;
+        value
+            .trim()
+            .parse()
+            .map_err(|e| self.error(io::Error::new(io::ErrorKind::InvalidData, e)))
+    }
+
+    fn write(&self, value: u32) -> Result<(), Error> {
+        fs::write(self.path.join("brightness"), value.to_string()).map_err(|e| self.error(e))
+    }
+}
+
+/// Controls the brightness of several displays as one.
+pub struct Brightness {
+    outputs: Vec<Output>,
+}
+
+impl Brightness {
+    /// Find every display whose brightness can be controlled.
+    pub fn new() -> Self {
+        let brightness = Self::from_displays(Display::enumerate());
+
+        #[cfg(target_os = "linux")]
+        let brightness = {
+            let mut brightness = brightness;
+            brightness.outputs.extend(
+                Backlight::enumerate()
+                    .into_iter()
+                    .map(|backlight| Output::Backlight(Box::new(backlight))),
+            );
+            brightness
+        };
+
+        brightness
+    }
+
+    /// Control the brightness of the specified displays only.
+    ///
+    /// Displays that fail to report their luminance are left out.
+    pub fn from_displays(displays: Vec<Display>) -> Self {
+        let outputs = displays
+            .into_iter()
+            .filter_map(|mut display| match display.get_vcp_feature(LUMINANCE) {
+                Ok(value) if value.maximum() > 0 => Some(Output::Ddc {
+                    maximum: value.maximum(),
+                    display: Box::new(display),
+                }),
+                Ok(_) => None,
+                Err(e) => {
+                    debug!("Leaving {} out of brightness control: {}", display.info, e);
+                    None
+                },
+            })
+            .collect();
+
+        Brightness { outputs }
+    }
+
+    /// The displays being controlled.
+    pub fn displays(&self) -> impl Iterator<Item = &DisplayInfo> {
+        self.outputs.iter().map(Output::info)
+    }
+
+    /// The average brightness of the displays, from 0 to 100, or `None` if
+    /// none of them could be read.
+    pub fn get(&mut self) -> Option<u8> {
+        trace!("Brightness::get");
+
+        let values: Vec<_> = self
+            .outputs
+            .iter_mut()
+            .filter_map(|output| match output.get() {
+                Ok(value) => Some(value as u32),
+                Err(e) => {
+                    debug!("Failed to read the brightness of {}: {}", output.info(), e);
+                    None
+                },
+            })
+            .collect();
+
+        match values.len() {
+            0 => None,
+            len => Some((values.iter().sum::<u32>() / len as u32) as u8),
+        }
+    }
+
+    /// Set the brightness of every display, from 0 to 100.
+    pub fn set(&mut self, percent: u8) -> BroadcastReport {
+        trace!("Brightness::set({})", percent);
+
+        let results = self
+            .outputs
+            .iter_mut()
+            .map(|output| {
+                let res = output.set(percent);
+                (output.info().clone(), res)
+            })
+            .collect();
+
+        BroadcastReport { results }
+    }
+}
+
+impl Default for Brightness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,80 @@
+//! Apply an operation to every display matching a query.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use ddc_hi::{broadcast, Query};
+//!
+//! let report = broadcast::set_brightness(&Query::Any, 50);
+//! for (info, error) in report.failures() {
+//!     eprintln!("{}: {}", info, error);
+//! }
+//! ```
+
+use {
+    crate::{Ddc, Display, DisplayInfo, Error, FeatureCode, Query},
+    log::trace,
+};
+
+/// The per-display outcome of a broadcast operation.
+#[derive(Debug, Default)]
+pub struct BroadcastReport {
+    /// Each display the operation was attempted on, and its result.
+    pub results: Vec<(DisplayInfo, Result<(), Error>)>,
+}
+
+impl BroadcastReport {
+    /// Whether the operation succeeded on every matching display.
+    ///
+    /// This is also true if no displays matched.
+    pub fn is_success(&self) -> bool {
+        self.results.iter().all(|(_, res)| res.is_ok())
+    }
+
+    /// The displays the operation failed on.
+    pub fn failures(&self) -> impl Iterator<Item = (&DisplayInfo, &Error)> {
+        self.results
+            .iter()
+            .filter_map(|(info, res)| res.as_ref().err().map(|e| (info, e)))
+    }
+}
+
+/// Run an operation on every enumerated display that matches `query`.
+///
+/// Queries are matched against the information available immediately after
+/// enumeration, which does not include the capabilities string.
+pub fn for_each<F: FnMut(&mut Display) -> Result<(), Error>>(query: &Query, mut f: F) -> BroadcastReport {
+    let results = Display::enumerate()
+        .into_iter()
+        .filter(|display| query.matches(&display.info))
+        .map(|mut display| {
+            trace!("broadcast to {}", display.info);
+            let res = f(&mut display);
+            (display.info, res)
+        })
+        .collect();
+
+    BroadcastReport { results }
+}
+
+/// Set a VCP feature on every display that matches `query`.
+pub fn set_feature(query: &Query, code: FeatureCode, value: u16) -> BroadcastReport {
+    for_each(query, |display| display.set_vcp_feature(code, value))
+}
+
+/// Set the luminance (`0x10`) of every display that matches `query`.
+pub fn set_brightness(query: &Query, value: u16) -> BroadcastReport {
+    set_feature(query, 0x10, value)
+}
+
+/// Switch the input source (`0x60`) of every display that matches `query`.
+///
+/// Each switch waits for its display to settle, as described by
+/// `Display::settle_time`.
+pub fn set_input_source(query: &Query, value: u16) -> BroadcastReport {
+    for_each(query, |display| {
+        display.set_vcp_feature(0x60, value)?;
+        display.settle(0x60);
+        Ok(())
+    })
+}
@@ -0,0 +1,291 @@
+//! A single owner of the enumerated displays that serves requests on behalf
+//! of other processes, with the `broker` feature.
+//!
+//! Desktop applets and other tools can talk to one privileged broker instead
+//! of each needing access to the displays, and the broker serializes their
+//! requests so they don't interfere with each other. The requests are
+//! modelled on a subset of ddcutil's D-Bus service, but this crate doesn't
+//! provide a D-Bus front-end. Requests can be handled directly with
+//! `Broker::handle`, or served over any byte stream with a simple line
+//! protocol using `Broker::serve`.
+//!
+//! # Line protocol
+//!
+//! Each request is a single line, one of `detect`, `list`,
+//! `capabilities <display>`, `getvcp <display> <code>` and
+//! `setvcp <display> <code> <value>`, where the feature code is hexadecimal.
+//! A response is zero or more result lines followed by either `ok` or
+//! `error <kind> <message>`. Result lines are `display <index> <backend>
+//! <id>`, `capabilities <string>` and `vcp <value> <maximum>`.
+//!
+//! On Windows, `Broker::serve_pipe` lets the application own the physical
+//! monitor handles and serve any number of other programs from a named pipe,
+//! rather than each of them opening the monitors concurrently.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{Backend, Ddc, Display, Error, ErrorKind, FeatureCode},
+    log::{debug, trace},
+    std::{
+        fmt,
+        io::{self, BufRead, Write},
+        str,
+    },
+};
+
+/// A request made to a `Broker`.
+///
+/// Displays are identified by their index in the most recent detection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Request {
+    /// Enumerate the connected displays again.
+    Detect,
+    /// List the displays found by the last detection.
+    List,
+    /// Read the capabilities string of a display.
+    GetCapabilitiesString {
+        /// The index of the display.
+        display: usize,
+    },
+    /// Read a VCP feature.
+    GetVcp {
+        /// The index of the display.
+        display: usize,
+        /// The feature to read.
+        code: FeatureCode,
+    },
+    /// Write a VCP feature.
+    SetVcp {
+        /// The index of the display.
+        display: usize,
+        /// The feature to write.
+        code: FeatureCode,
+        /// The value to write.
+        value: u16,
+    },
+}
+
+/// A display as described to broker clients.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BrokerDisplay {
+    /// Identifies the backend or driver used to communicate with the display.
+    pub backend: Backend,
+    /// A unique identifier for the display, format is specific to the backend.
+    pub id: String,
+    /// A three-character identifier of the manufacturer of the display.
+    pub manufacturer_id: Option<String>,
+    /// The model name of the display.
+    pub model_name: Option<String>,
+    /// Human-readable serial number of the device.
+    pub serial_number: Option<String>,
+}
+
+/// The reply to a `Request`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Response {
+    /// The detected displays.
+    Displays(Vec<BrokerDisplay>),
+    /// The capabilities string of a display.
+    CapabilitiesString(String),
+    /// The current and maximum value of a VCP feature.
+    Vcp {
+        /// The current value.
+        value: u16,
+        /// The maximum value.
+        maximum: u16,
+    },
+    /// The request succeeded without a result.
+    Ok,
+    /// The request failed.
+    Error {
+        /// The classification of the error.
+        kind: ErrorKind,
+        /// A description of the error.
+        message: String,
+    },
+}
+
+impl str::FromStr for Request {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidArgument(format!("invalid request {:?}", s));
+        let mut words = s.split_whitespace();
+        let command = words.next().ok_or_else(invalid)?;
+        let mut display = || words.next().and_then(|w| w.parse().ok()).ok_or_else(invalid);
+        let request = match command {
+            "detect" => Request::Detect,
+            "list" => Request::List,
+            "capabilities" => Request::GetCapabilitiesString { display: display()? },
+            "getvcp" | "setvcp" => {
+                let display = display()?;
+                let code = words
+                    .next()
+                    .and_then(|w| u8::from_str_radix(w.trim_start_matches("0x"), 16).ok())
+                    .ok_or_else(invalid)?;
+                match command {
+                    "getvcp" => Request::GetVcp { display, code },
+                    _ => Request::SetVcp {
+                        display,
+                        code,
+                        value: words.next().and_then(|w| w.parse().ok()).ok_or_else(invalid)?,
+                    },
+                }
+            },
+            _ => return Err(invalid()),
+        };
+
+        match words.next() {
+            Some(_) => Err(invalid()),
+            None => Ok(request),
+        }
+    }
+}
+
+impl fmt::Display for Request {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Request::Detect => write!(f, "detect"),
+            Request::List => write!(f, "list"),
+            Request::GetCapabilitiesString { display } => write!(f, "capabilities {}", display),
+            Request::GetVcp { display, code } => write!(f, "getvcp {} {:02x}", display, code),
+            Request::SetVcp { display, code, value } => write!(f, "setvcp {} {:02x} {}", display, code, value),
+        }
+    }
+}
+
+impl fmt::Display for Response {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Response::Displays(ref displays) =>
+                for (i, display) in displays.iter().enumerate() {
+                    writeln!(f, "display {} {} {}", i, display.backend, display.id)?;
+                },
+            Response::CapabilitiesString(ref caps) => writeln!(f, "capabilities {}", caps)?,
+            Response::Vcp { value, maximum } => writeln!(f, "vcp {} {}", value, maximum)?,
+            Response::Ok => (),
+            Response::Error { kind, ref message } =>
+                return writeln!(f, "error {:?} {}", kind, message.replace('\n', " ")),
+        }
+
+        writeln!(f, "ok")
+    }
+}
+
+impl From<Error> for Response {
+    fn from(e: Error) -> Self {
+        Response::Error {
+            kind: e.kind(),
+            message: e.to_string(),
+        }
+    }
+}
+
+/// Owns the displays and handles requests for them one at a time.
+#[derive(Default)]
+pub struct Broker {
+    displays: Vec<Display>,
+}
+
+impl Broker {
+    /// Create a broker and detect the connected displays.
+    pub fn new() -> Self {
+        Broker {
+            displays: Display::enumerate(),
+        }
+    }
+
+    /// The displays found by the last detection.
+    pub fn displays(&mut self) -> &mut [Display] {
+        &mut self.displays
+    }
+
+    fn list(&self) -> Response {
+        Response::Displays(
+            self.displays
+                .iter()
+                .map(|display| BrokerDisplay {
+                    backend: display.info.backend,
+                    id: display.info.id.clone(),
+                    manufacturer_id: display.info.manufacturer_id.clone(),
+                    model_name: display.info.model_name.clone(),
+                    serial_number: display.info.serial_number.clone(),
+                })
+                .collect(),
+        )
+    }
+
+    fn display(&mut self, index: usize) -> Result<&mut Display, Error> {
+        self.displays
+            .get_mut(index)
+            .ok_or_else(|| Error::InvalidArgument(format!("no display {}", index)))
+    }
+
+    /// Handle a single request.
+    pub fn handle(&mut self, request: &Request) -> Response {
+        trace!("Broker::handle({:?})", request);
+
+        let res = match *request {
+            Request::Detect => {
+                self.displays = Display::enumerate();
+                return self.list()
+            },
+            Request::List => return self.list(),
+            Request::GetCapabilitiesString { display } => self.display(display).and_then(|display| {
+                display
+                    .capabilities_string()
+                    .map(|caps| Response::CapabilitiesString(String::from_utf8_lossy(&caps).into_owned()))
+            }),
+            Request::GetVcp { display, code } => self.display(display).and_then(|display| {
+                display.get_vcp_feature(code).map(|value| Response::Vcp {
+                    value: value.value(),
+                    maximum: value.maximum(),
+                })
+            }),
+            Request::SetVcp { display, code, value } => self
+                .display(display)
+                .and_then(|display| display.set_vcp_feature(code, value).map(|()| Response::Ok)),
+        };
+
+        res.unwrap_or_else(From::from)
+    }
+
+    /// Serve requests using the line protocol until the stream is closed.
+    pub fn serve<S: BufRead + Write>(&mut self, mut stream: S) -> io::Result<()> {
+        trace!("Broker::serve");
+
+        serve_lines(&mut stream, |request| self.handle(request))
+    }
+}
+
+/// Serve requests using the line protocol until the stream is closed,
+/// passing each to `handle`.
+pub(crate) fn serve_lines<S: BufRead + Write, F: FnMut(&Request) -> Response>(
+    stream: &mut S,
+    mut handle: F,
+) -> io::Result<()> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if stream.read_line(&mut line)? == 0 {
+            return Ok(())
+        }
+        if line.trim().is_empty() {
+            continue
+        }
+
+        let response = match line.parse() {
+            Ok(request) => handle(&request),
+            Err(e) => {
+                debug!("Broker received an invalid request: {}", e);
+                Response::from(e)
+            },
+        };
+        write!(stream, "{}", response)?;
+        stream.flush()?;
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{Backend, CapabilitiesInfo, DisplayAdapter, DisplayBounds, DisplayInfo};
+
+/// Builds a `DisplayInfo`, leaving anything not set unknown.
+///
+/// Unlike filling in the fields of `DisplayInfo` directly, code using the
+/// builder keeps compiling as fields are added.
+///
+/// # Example
+///
+/// ```rust
+/// use ddc_hi::{Backend, DisplayInfo};
+///
+/// let info = DisplayInfo::builder(Backend::I2cDevice, "/dev/i2c-4")
+///     .manufacturer_id("DEL")
+///     .model_name("DELL U2720Q")
+///     .serial_number("ABC123")
+///     .build();
+/// assert_eq!(info.model_name.as_deref(), Some("DELL U2720Q"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct DisplayInfoBuilder {
+    info: DisplayInfo,
+}
+
+impl DisplayInfoBuilder {
+    /// Start building the info of a display.
+    pub fn new<S: Into<String>>(backend: Backend, id: S) -> Self {
+        DisplayInfoBuilder {
+            info: DisplayInfo::new(backend, id.into()),
+        }
+    }
+
+    /// Set the three-character identifier of the manufacturer.
+    pub fn manufacturer_id<S: Into<String>>(mut self, id: S) -> Self {
+        self.info.manufacturer_id = Some(id.into());
+        self
+    }
+
+    /// Set the number that identifies the product model.
+    pub fn model_id(mut self, id: u16) -> Self {
+        self.info.model_id = Some(id);
+        self
+    }
+
+    /// Set the version and revision of the product.
+    pub fn version(mut self, version: u8, revision: u8) -> Self {
+        self.info.version = Some((version, revision));
+        self
+    }
+
+    /// Set the serial number of the device.
+    pub fn serial(mut self, serial: u32) -> Self {
+        self.info.serial = Some(serial);
+        self
+    }
+
+    /// Set the raw EDID week and year of manufacture.
+    pub fn manufacture_date(mut self, week: u8, year: u8) -> Self {
+        self.info.manufacture_week = Some(week);
+        self.info.manufacture_year = Some(year);
+        self
+    }
+
+    /// Set the model name of the display.
+    pub fn model_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.info.model_name = Some(name.into());
+        self
+    }
+
+    /// Set the human-readable serial number of the device.
+    pub fn serial_number<S: Into<String>>(mut self, serial: S) -> Self {
+        self.info.serial_number = Some(serial.into());
+        self
+    }
+
+    /// Set the raw EDID data, without parsing it.
+    pub fn edid_data(mut self, edid: Vec<u8>) -> Self {
+        self.info.edid_data = Some(edid);
+        self
+    }
+
+    /// Set the MCCS VCP version.
+    pub fn mccs_version(mut self, version: mccs::Version) -> Self {
+        self.info.mccs_version = Some(version);
+        self
+    }
+
+    /// Set the graphics adapter the display is connected to.
+    pub fn adapter(mut self, adapter: DisplayAdapter) -> Self {
+        self.info.adapter = Some(adapter);
+        self
+    }
+
+    /// Set the CoreGraphics display ID.
+    pub fn core_graphics_id(mut self, id: u32) -> Self {
+        self.info.core_graphics_id = Some(id);
+        self
+    }
+
+    /// Set the NVAPI display ID.
+    pub fn nvapi_display_id(mut self, id: u32) -> Self {
+        self.info.nvapi_display_id = Some(id);
+        self
+    }
+
+    /// Set the Linux DRM connector, such as `card0-HDMI-A-1`.
+    pub fn drm_connector<S: Into<String>>(mut self, connector: S) -> Self {
+        self.info.drm_connector = Some(connector.into());
+        self
+    }
+
+    /// Set the parts of the capabilities string that don't describe VCP
+    /// features.
+    pub fn capabilities(mut self, capabilities: CapabilitiesInfo) -> Self {
+        self.info.capabilities = Some(capabilities);
+        self
+    }
+
+    /// Set where the display is placed on the desktop.
+    pub fn bounds(mut self, bounds: DisplayBounds) -> Self {
+        self.info.bounds = Some(bounds);
+        self
+    }
+
+    /// Set whether the display is built into the computer.
+    pub fn internal(mut self, internal: bool) -> Self {
+        self.info.is_internal = Some(internal);
+        self
+    }
+
+    /// Set the MCCS VCP feature information.
+    #[cfg(feature = "mccs-db")]
+    pub fn mccs_database(mut self, database: mccs_db::Database) -> Self {
+        self.info.mccs_database = database;
+        self
+    }
+
+    /// Finish building.
+    pub fn build(self) -> DisplayInfo {
+        self.info
+    }
+}
+
+impl DisplayInfo {
+    /// Start building the info of a display.
+    pub fn builder<S: Into<String>>(backend: Backend, id: S) -> DisplayInfoBuilder {
+        DisplayInfoBuilder::new(backend, id)
+    }
+}
@@ -0,0 +1,102 @@
+//! Tracks when each physical bus is next ready for a command.
+//!
+//! A handle only knows about the commands it sent itself, so this lets
+//! separate handles to the same bus respect each other's delays while
+//! commands to other buses proceed without waiting.
+
+use {
+    crate::{Backend, Display, DisplayInfo, Handle},
+    std::{
+        collections::BTreeMap,
+        sync::Mutex,
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+/// Identifies the physical bus a display is reached through, such as an
+/// i2c-dev device or a WinAPI adapter output.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Bus(Backend, String);
+
+impl Bus {
+    /// The bus of a display, which is only known to be distinct from other
+    /// displays' by its id unless the handle says otherwise.
+    pub(crate) fn from_info(info: &DisplayInfo) -> Self {
+        Bus(info.backend, info.id.clone())
+    }
+
+    /// The bus that a built-in handle communicates over.
+    #[cfg_attr(
+        not(any(feature = "has-ddc-i2c", feature = "has-ddc-winapi")),
+        allow(unused_variables)
+    )]
+    pub(crate) fn from_handle(handle: &Handle, info: &DisplayInfo) -> Self {
+        match *handle {
+            #[cfg(feature = "has-ddc-i2c")]
+            Handle::I2cDevice(ref ddc) => {
+                use std::os::unix::fs::MetadataExt;
+
+                if let Ok(meta) = ddc.inner_ref().inner_ref().metadata() {
+                    return Bus(info.backend, format!("rdev:{}", meta.rdev()))
+                }
+            },
+            #[cfg(feature = "has-ddc-winapi")]
+            Handle::WinApi(ref monitor) => {
+                // identical models share a description, but not an output
+                let output = info
+                    .adapter
+                    .as_ref()
+                    .and_then(|adapter| Some((adapter.luid?, adapter.output?)));
+                return Bus(info.backend, match output {
+                    Some((luid, output)) => format!("output:{:x}:{}", luid, output),
+                    None => format!("monitor:{:p}", monitor.handle()),
+                })
+            },
+            #[allow(unreachable_patterns)]
+            _ => (),
+        }
+
+        Self::from_info(info)
+    }
+}
+
+static READY: Mutex<BTreeMap<Bus, Instant>> = Mutex::new(BTreeMap::new());
+
+/// How long until the bus is ready for another command.
+pub(crate) fn remaining(bus: &Bus) -> Duration {
+    READY
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(bus)
+        .and_then(|ready| ready.checked_duration_since(Instant::now()))
+        .unwrap_or_default()
+}
+
+/// Wait until the bus is ready for another command.
+pub(crate) fn wait(bus: &Bus) {
+    let remaining = remaining(bus);
+    if !remaining.is_zero() {
+        thread::sleep(remaining);
+    }
+}
+
+/// Record that the bus is busy for `delay` from now.
+pub(crate) fn busy(bus: &Bus, delay: Duration) {
+    let ready = Instant::now() + delay;
+    let mut buses = READY.lock().unwrap_or_else(|e| e.into_inner());
+    let entry = buses.entry(bus.clone()).or_insert(ready);
+    *entry = (*entry).max(ready);
+}
+
+impl Display {
+    /// Create a display from one of the built-in handles, tracking the
+    /// readiness of the bus it communicates over.
+    pub(crate) fn from_handle(handle: Handle, info: DisplayInfo) -> Self {
+        let bus = Bus::from_handle(&handle, &info);
+        Display {
+            bus,
+            ..Display::new(handle, info)
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use {
+    crate::{Ddc, Display, Error, FeatureCode, VcpValue},
+    log::trace,
+    std::{
+        collections::BTreeMap,
+        time::{Duration, Instant},
+    },
+};
+
+/// Recently read VCP feature values.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ValueCache {
+    ttl: Option<Duration>,
+    values: BTreeMap<FeatureCode, (Instant, VcpValue)>,
+}
+
+impl ValueCache {
+    fn get(&self, code: FeatureCode) -> Option<VcpValue> {
+        let ttl = self.ttl?;
+        self.values
+            .get(&code)
+            .filter(|&&(read, _)| read.elapsed() < ttl)
+            .map(|&(_, value)| value)
+    }
+
+    fn insert(&mut self, code: FeatureCode, value: VcpValue) {
+        if self.ttl.is_some() {
+            self.values.insert(code, (Instant::now(), value));
+        }
+    }
+
+    /// Forget the value of a feature after it has been written.
+    pub(crate) fn invalidate(&mut self, code: FeatureCode) {
+        self.values.remove(&code);
+    }
+
+    /// Forget all values, for operations that may change any of them.
+    pub(crate) fn clear(&mut self) {
+        self.values.clear();
+    }
+}
+
+impl Display {
+    /// Read a VCP feature, reusing a value read within the cache TTL.
+    ///
+    /// This behaves like `get_vcp_feature` unless a TTL has been set with
+    /// `set_cache_ttl`. Writes made through this `Display` invalidate the
+    /// affected values, but changes made by other handles or from the
+    /// display's own menus are not seen until the TTL expires.
+    pub fn cached(&mut self, code: FeatureCode) -> Result<VcpValue, Error> {
+        trace!("Display::cached(0x{:02x})", code);
+
+        if let Some(value) = self.cache.get(code) {
+            return Ok(value)
+        }
+
+        let value = self.get_vcp_feature(code)?;
+        self.cache.insert(code, value);
+        Ok(value)
+    }
+
+    /// How long values read by `cached` are reused for.
+    pub fn cache_ttl(&self) -> Option<Duration> {
+        self.cache.ttl
+    }
+
+    /// Change how long values read by `cached` are reused for, or disable
+    /// caching with `None`.
+    pub fn set_cache_ttl(&mut self, ttl: Option<Duration>) {
+        self.cache.ttl = ttl;
+        if ttl.is_none() {
+            self.cache.clear();
+        }
+    }
+}
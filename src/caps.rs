@@ -0,0 +1,115 @@
+use {
+    crate::{delays::Commands, Ddc, Display, DisplayInfo, Error, Handle, Operation},
+    ddc::{commands::CapabilitiesRequest, DdcCommand, ErrorCode},
+    log::debug,
+    std::{any::Any, mem},
+};
+
+/// A capabilities string that could only be partially read.
+pub(crate) struct PartialCapabilities {
+    /// The chunks read so far.
+    data: Vec<u8>,
+    /// The display info from before the partial string was applied.
+    base: DisplayInfo,
+}
+
+/// Read the rest of a capabilities string, one chunk at a time.
+///
+/// Chunks are appended to `data` as they arrive, so a failed read can be
+/// resumed from where it left off.
+fn read_chunks<D: DdcCommand<Error = Error>>(ddc: &mut D, data: &mut Vec<u8>) -> Result<(), Error> {
+    loop {
+        let offset = data.len() as u16;
+        let reply = ddc.execute(CapabilitiesRequest::new(offset))?;
+        if reply.offset != offset {
+            return Err(ErrorCode::InvalidOffset.into())
+        } else if reply.data.is_empty() {
+            return Ok(())
+        }
+
+        data.extend(reply.data.iter());
+    }
+}
+
+/// Parse as much of a truncated capabilities string as possible.
+///
+/// The last incomplete value is dropped and any open parentheses are closed.
+fn parse_prefix(data: &[u8]) -> Option<mccs::Capabilities> {
+    let end = data
+        .iter()
+        .rposition(|&b| matches!(b, b' ' | b'(' | b')'))
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let mut data = data[..end].to_vec();
+    let depth = data.iter().fold(0isize, |depth, &b| match b {
+        b'(' => depth + 1,
+        b')' => depth - 1,
+        _ => depth,
+    });
+    data.extend((0..depth).map(|_| b')'));
+
+    mccs_caps::parse_capabilities(&data).ok()
+}
+
+impl<H: Ddc<Error = Error> + 'static> Display<H> {
+    /// Updates the display info with data retrieved from the device's
+    /// reported capabilities.
+    ///
+    /// If the capabilities string can only be partially read, the info is
+    /// updated from the part that was read and the error is returned. A
+    /// later call then only reads the remainder; see
+    /// `Display::capabilities_partial`. Backends that can't execute individual
+    /// commands always read the whole string.
+    pub fn update_capabilities(&mut self) -> Result<(), Error> {
+        if self.filled_caps {
+            return Ok(())
+        }
+
+        let delays = self.delays;
+        let mut data = self
+            .partial_caps
+            .as_mut()
+            .map(|partial| mem::take(&mut partial.data))
+            .unwrap_or_default();
+        let res = self.run(Operation::Capabilities, None, |handle| {
+            match (handle as &mut dyn Any)
+                .downcast_mut::<Handle>()
+                .and_then(|handle| Commands::supported(handle, delays))
+            {
+                Some(mut ddc) => read_chunks(&mut ddc, &mut data),
+                None => handle.capabilities_string().map(|caps| data = caps),
+            }
+        });
+
+        let base = self.partial_caps.take().map(|partial| partial.base);
+        if let Err(e) = res {
+            if !data.is_empty() {
+                debug!("Read {} bytes of the capabilities of {}", data.len(), self.info);
+                let base = base.unwrap_or_else(|| self.info.clone());
+                if let Some(caps) = parse_prefix(&data) {
+                    self.info = base.clone();
+                    self.fill_capabilities(&caps);
+                }
+                self.partial_caps = Some(PartialCapabilities { data, base });
+            }
+            return Err(e)
+        }
+
+        let caps = mccs_caps::parse_capabilities(&data).map_err(|e| self.context(Error::CapabilitiesParseError(e)))?;
+        if let Some(base) = base {
+            self.info = base;
+        }
+        self.fill_capabilities(&caps);
+        self.filled_caps = true;
+
+        Ok(())
+    }
+}
+
+impl<H> Display<H> {
+    /// Whether the display info was only updated from part of the
+    /// capabilities string, because reading the rest of it failed.
+    pub fn capabilities_partial(&self) -> bool {
+        self.partial_caps.is_some()
+    }
+}
@@ -0,0 +1,94 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{DisplayInfo, FeatureCode},
+    std::collections::BTreeMap,
+};
+
+/// A feature described by a display's MCCS database, in a form that can be
+/// exported without depending on `mccs_db`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FeatureDescription {
+    /// The VCP code of the feature.
+    pub code: FeatureCode,
+    /// The name of the feature.
+    pub name: Option<String>,
+    /// A detailed description of the feature.
+    pub description: Option<String>,
+    /// The MCCS grouping this feature belongs to.
+    pub group: Option<String>,
+    /// The type of the feature's value.
+    pub kind: FeatureKind,
+    /// Whether the feature can be read, written, or both.
+    pub access: FeatureAccess,
+    /// Whether the MCCS specification requires displays to support the
+    /// feature.
+    pub mandatory: bool,
+    /// Other features whose values may change along with this one.
+    pub interacts_with: Vec<FeatureCode>,
+    /// The allowed values of a non-continuous feature and their names.
+    pub values: BTreeMap<u8, Option<String>>,
+}
+
+/// The type of a feature's value.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum FeatureKind {
+    /// The type is not known.
+    Unknown,
+    /// A value from zero up to the maximum the display reports.
+    Continuous,
+    /// One of a set of allowed values.
+    NonContinuous,
+    /// A table of bytes.
+    Table,
+}
+
+/// Whether a feature can be read, written, or both.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum FeatureAccess {
+    /// The feature can only be read.
+    ReadOnly,
+    /// The feature can only be written.
+    WriteOnly,
+    /// The feature can be both read and written.
+    ReadWrite,
+}
+
+impl From<&mccs_db::Descriptor> for FeatureDescription {
+    fn from(desc: &mccs_db::Descriptor) -> Self {
+        let (kind, values) = match desc.ty {
+            mccs_db::ValueType::Unknown => (FeatureKind::Unknown, Default::default()),
+            mccs_db::ValueType::Continuous { .. } => (FeatureKind::Continuous, Default::default()),
+            mccs_db::ValueType::NonContinuous { ref values, .. } => (FeatureKind::NonContinuous, values.clone()),
+            mccs_db::ValueType::Table { .. } => (FeatureKind::Table, Default::default()),
+        };
+
+        FeatureDescription {
+            code: desc.code,
+            name: desc.name.clone(),
+            description: desc.description.clone(),
+            group: desc.group.clone(),
+            kind,
+            access: match desc.access {
+                mccs_db::Access::ReadOnly => FeatureAccess::ReadOnly,
+                mccs_db::Access::WriteOnly => FeatureAccess::WriteOnly,
+                mccs_db::Access::ReadWrite => FeatureAccess::ReadWrite,
+            },
+            mandatory: desc.mandatory,
+            interacts_with: desc.interacts_with.clone(),
+            values,
+        }
+    }
+}
+
+impl DisplayInfo {
+    /// Describe every feature in `mccs_database`, ordered by code.
+    pub fn feature_descriptions(&self) -> Vec<FeatureDescription> {
+        self.features().map(From::from).collect()
+    }
+}
@@ -0,0 +1,319 @@
+//! Interoperability with the file formats used by ddcutil.
+//!
+//! This covers ddcutil's capabilities cache, so that capabilities it has
+//! already read can be reused, and the feature dumps written by
+//! `ddcutil dumpvcp` and read by `ddcutil loadvcp`.
+
+use {
+    crate::{DisplayInfo, FeatureCode, Profile, VcpTransaction},
+    std::{collections::BTreeMap, env, fmt, io, path::PathBuf},
+};
+
+fn invalid_data<E: fmt::Display>(e: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+}
+
+/// An entry in ddcutil's capabilities cache.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CachedCapabilities {
+    /// A three-character identifier of the manufacturer of the display.
+    pub manufacturer_id: String,
+    /// The model name of the display.
+    pub model_name: String,
+    /// A number that identifies the product model.
+    pub product_code: u16,
+    /// The raw capabilities string.
+    pub capabilities: String,
+}
+
+impl CachedCapabilities {
+    /// Whether the entry describes the same model as the display.
+    pub fn matches(&self, info: &DisplayInfo) -> bool {
+        info.manufacturer_id.as_deref() == Some(&self.manufacturer_id[..])
+            && info.model_name.as_deref() == Some(&self.model_name[..])
+            && info.model_id == Some(self.product_code)
+    }
+}
+
+impl fmt::Display for CachedCapabilities {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{} {}",
+            self.manufacturer_id, self.model_name, self.product_code, self.capabilities
+        )
+    }
+}
+
+/// The location of ddcutil's capabilities cache for the current user.
+pub fn capabilities_cache_path() -> Option<PathBuf> {
+    let cache = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(cache.join("ddcutil").join("capabilities"))
+}
+
+/// Parse the contents of ddcutil's capabilities cache.
+pub fn parse_capabilities_cache(data: &str) -> io::Result<Vec<CachedCapabilities>> {
+    data.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            // the model name may contain spaces, but the capabilities string
+            // always starts with a parenthesis
+            let split = line
+                .find(" (")
+                .ok_or_else(|| invalid_data(format!("missing capabilities in {:?}", line)))?;
+            let (key, capabilities) = (&line[..split], line[split + 1..].trim());
+            let mut key = key.splitn(2, ':');
+            let manufacturer_id = key.next().unwrap_or_default();
+            let (model_name, product_code) = key
+                .next()
+                .and_then(|key| key.rsplit_once(':'))
+                .ok_or_else(|| invalid_data(format!("malformed key in {:?}", line)))?;
+
+            Ok(CachedCapabilities {
+                manufacturer_id: manufacturer_id.into(),
+                model_name: model_name.into(),
+                product_code: product_code.parse().map_err(invalid_data)?,
+                capabilities: capabilities.into(),
+            })
+        })
+        .collect()
+}
+
+/// Write entries in the format of ddcutil's capabilities cache.
+pub fn write_capabilities_cache(entries: &[CachedCapabilities]) -> String {
+    let mut data = String::from("# Format=1\n");
+    for entry in entries {
+        data.push_str(&entry.to_string());
+        data.push('\n');
+    }
+    data
+}
+
+/// A feature dump as written by `ddcutil dumpvcp`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VcpDump {
+    /// A three-character identifier of the manufacturer of the display.
+    pub manufacturer_id: Option<String>,
+    /// The model name of the display.
+    pub model_name: Option<String>,
+    /// A number that identifies the product model.
+    pub product_code: Option<u16>,
+    /// Human-readable serial number of the device.
+    pub serial_number: Option<String>,
+    /// Raw EDID data provided by the display.
+    pub edid: Option<Vec<u8>>,
+    /// The MCCS version as a major and minor number.
+    pub mccs_version: Option<(u8, u8)>,
+    /// The dumped feature values.
+    pub values: BTreeMap<FeatureCode, u16>,
+}
+
+impl VcpDump {
+    /// Parse a dump. Unrecognized lines are ignored.
+    pub fn parse(data: &str) -> io::Result<Self> {
+        let mut dump = VcpDump::default();
+        for line in data.lines().map(str::trim) {
+            let (key, value) = match line.split_once(char::is_whitespace) {
+                Some((key, value)) => (key, value.trim()),
+                None => continue,
+            };
+            match key {
+                "MFG_ID" => dump.manufacturer_id = Some(value.into()),
+                "MODEL" => dump.model_name = Some(value.into()),
+                "PRODUCT_CODE" => dump.product_code = Some(value.parse().map_err(invalid_data)?),
+                "SN" => dump.serial_number = Some(value.into()),
+                "EDID" => {
+                    if !value.is_ascii() || value.len() % 2 != 0 {
+                        return Err(invalid_data(format!("malformed EDID {:?}", value)))
+                    }
+                    dump.edid = Some(
+                        value
+                            .as_bytes()
+                            .chunks(2)
+                            .map(|byte| u8::from_str_radix(std::str::from_utf8(byte).unwrap_or_default(), 16))
+                            .collect::<Result<_, _>>()
+                            .map_err(invalid_data)?,
+                    );
+                },
+                "VCP_VERSION" => {
+                    let (major, minor) = value
+                        .split_once('.')
+                        .ok_or_else(|| invalid_data(format!("malformed VCP version {:?}", value)))?;
+                    dump.mccs_version = Some((
+                        major.parse().map_err(invalid_data)?,
+                        minor.parse().map_err(invalid_data)?,
+                    ));
+                },
+                "VCP" => {
+                    let (code, value) = value
+                        .split_once(char::is_whitespace)
+                        .ok_or_else(|| invalid_data(format!("malformed VCP value {:?}", line)))?;
+                    dump.values.insert(
+                        u8::from_str_radix(code, 16).map_err(invalid_data)?,
+                        value.trim().parse().map_err(invalid_data)?,
+                    );
+                },
+                _ => (),
+            }
+        }
+
+        Ok(dump)
+    }
+
+    /// Create a dump of a profile.
+    pub fn from_profile(profile: &Profile) -> Self {
+        VcpDump {
+            manufacturer_id: profile.manufacturer_id.clone(),
+            model_name: profile.model_name.clone(),
+            product_code: profile.model_id,
+            serial_number: profile.serial_number.clone(),
+            edid: None,
+            mccs_version: None,
+            values: profile.values.clone(),
+        }
+    }
+
+    /// Convert the dump into a profile for the specified display.
+    pub fn to_profile(&self, info: &DisplayInfo) -> Profile {
+        let mut profile = Profile::new(info);
+        profile.manufacturer_id = self.manufacturer_id.clone();
+        profile.model_id = self.product_code;
+        profile.model_name = self.model_name.clone();
+        profile.serial_number = self.serial_number.clone();
+        profile.values = self.values.clone();
+        profile
+    }
+
+    /// Whether the dump appears to describe the same model of display.
+    pub fn matches(&self, info: &DisplayInfo) -> bool {
+        self.to_profile(info).matches(info)
+    }
+
+    /// A transaction that restores the dumped values.
+    pub fn transaction(&self) -> VcpTransaction {
+        self.values
+            .iter()
+            .fold(VcpTransaction::new(), |tx, (&code, &value)| tx.set(code, value))
+    }
+}
+
+impl fmt::Display for VcpDump {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ref id) = self.manufacturer_id {
+            writeln!(f, "MFG_ID  {}", id)?;
+        }
+        if let Some(ref model) = self.model_name {
+            writeln!(f, "MODEL  {}", model)?;
+        }
+        if let Some(code) = self.product_code {
+            writeln!(f, "PRODUCT_CODE  {}", code)?;
+        }
+        if let Some(ref serial) = self.serial_number {
+            writeln!(f, "SN  {}", serial)?;
+        }
+        if let Some(ref edid) = self.edid {
+            write!(f, "EDID  ")?;
+            for b in edid {
+                write!(f, "{:02X}", b)?;
+            }
+            writeln!(f)?;
+        }
+        if let Some((major, minor)) = self.mccs_version {
+            writeln!(f, "VCP_VERSION {}.{}", major, minor)?;
+        }
+        for (code, value) in &self.values {
+            writeln!(f, "VCP {:02X} {:5}", code, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cached() -> Vec<CachedCapabilities> {
+        vec![
+            CachedCapabilities {
+                manufacturer_id: "DEL".into(),
+                model_name: "DELL U2720Q".into(),
+                product_code: 41232,
+                capabilities: "(prot(monitor)type(LCD)vcp(10 12 60(0F 11))mccs_ver(2.1))".into(),
+            },
+            CachedCapabilities {
+                manufacturer_id: "GSM".into(),
+                model_name: "LG:HDR".into(),
+                product_code: 7,
+                capabilities: "(vcp(10))".into(),
+            },
+        ]
+    }
+
+    #[test]
+    fn capabilities_cache_round_trip() {
+        let entries = cached();
+        let data = write_capabilities_cache(&entries);
+        assert!(data.starts_with("# Format=1\n"));
+        assert_eq!(parse_capabilities_cache(&data).unwrap(), entries);
+    }
+
+    #[test]
+    fn capabilities_cache_parse() {
+        let data = "# comment\n\nDEL:DELL P2419H:53482 (vcp(10 12))\n";
+        let entries = parse_capabilities_cache(data).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].manufacturer_id, "DEL");
+        assert_eq!(entries[0].model_name, "DELL P2419H");
+        assert_eq!(entries[0].product_code, 53482);
+        assert_eq!(entries[0].capabilities, "(vcp(10 12))");
+    }
+
+    #[test]
+    fn capabilities_cache_malformed() {
+        assert!(parse_capabilities_cache("DEL:U2720Q:1").is_err());
+        assert!(parse_capabilities_cache("DEL (vcp(10))").is_err());
+        assert!(parse_capabilities_cache("DEL:U2720Q:x (vcp(10))").is_err());
+    }
+
+    #[test]
+    fn vcp_dump_round_trip() {
+        let dump = VcpDump {
+            manufacturer_id: Some("DEL".into()),
+            model_name: Some("DELL U2720Q".into()),
+            product_code: Some(41232),
+            serial_number: Some("ABC123".into()),
+            edid: Some(vec![0x00, 0xff, 0x10, 0xab]),
+            mccs_version: Some((2, 1)),
+            values: [(0x10, 75), (0x60, 0x0f)].into_iter().collect(),
+        };
+        assert_eq!(VcpDump::parse(&dump.to_string()).unwrap(), dump);
+    }
+
+    #[test]
+    fn vcp_dump_parse() {
+        let data = "MFG_ID  DEL\nTIMESTAMP_TEXT  whenever\nVCP_VERSION 2.1\nVCP 10    50\nEDID  00FF\n";
+        let dump = VcpDump::parse(data).unwrap();
+        assert_eq!(dump.manufacturer_id.as_deref(), Some("DEL"));
+        assert_eq!(dump.mccs_version, Some((2, 1)));
+        assert_eq!(dump.values.get(&0x10), Some(&50));
+        assert_eq!(dump.edid, Some(vec![0x00, 0xff]));
+    }
+
+    #[test]
+    fn vcp_dump_malformed_edid() {
+        assert!(VcpDump::parse("EDID  00F").is_err());
+        assert!(VcpDump::parse("EDID  00é0").is_err());
+        assert!(VcpDump::parse("EDID  0G").is_err());
+    }
+
+    #[test]
+    fn vcp_dump_malformed() {
+        assert!(VcpDump::parse("VCP_VERSION 2").is_err());
+        assert!(VcpDump::parse("VCP 10").is_err());
+        assert!(VcpDump::parse("VCP zz 10").is_err());
+    }
+}
@@ -0,0 +1,191 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{bus, Display, Error, Handle, Operation},
+    ddc::{
+        commands::{self, Command, CommandResult},
+        DdcCommand, DdcCommandMarker, DdcCommandRawMarker, DdcHost, Delay, ErrorCode, DELAY_COMMAND_FAILED_MS,
+    },
+    std::time::Duration,
+};
+
+/// The delays waited around DDC/CI commands, as percentages of those the
+/// DDC/CI specification requires.
+///
+/// Some displays need two or three times the specified delays to respond
+/// reliably, while others work with much less. Only the i2c-dev and NVAPI
+/// backends allow the delays to be changed; the others always use the
+/// specified delays.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Delays {
+    /// The delay between sending a request and reading its reply.
+    pub response: u32,
+    /// The delay after a command completes before the next may be sent.
+    pub command: u32,
+}
+
+impl Default for Delays {
+    fn default() -> Self {
+        Self::SPEC
+    }
+}
+
+impl Delays {
+    /// The delays required by the DDC/CI specification.
+    pub const SPEC: Delays = Delays {
+        response: 100,
+        command: 100,
+    };
+
+    /// Scale all of the specified delays by a percentage.
+    pub fn scaled(percent: u32) -> Self {
+        Delays {
+            response: percent,
+            command: percent,
+        }
+    }
+
+    /// The delay before reading the reply to a command.
+    pub fn response_delay<C: Command>(&self) -> Duration {
+        Self::scale(C::DELAY_RESPONSE_MS, self.response)
+    }
+
+    /// The delay after a command before the next may be sent.
+    pub fn command_delay<C: Command>(&self) -> Duration {
+        Self::scale(C::DELAY_COMMAND_MS, self.command)
+    }
+
+    /// The delay after a failed command before the next may be sent.
+    pub fn failed_command_delay(&self) -> Duration {
+        Self::scale(DELAY_COMMAND_FAILED_MS, self.command)
+    }
+
+    /// The delay after a high level operation before the next command may
+    /// be sent.
+    pub(crate) fn operation_delay(&self, op: Operation, failed: bool) -> Duration {
+        match op {
+            _ if failed => self.failed_command_delay(),
+            Operation::Capabilities => self.command_delay::<commands::CapabilitiesRequest>(),
+            Operation::GetVcpFeature => self.command_delay::<commands::GetVcpFeature>(),
+            Operation::SetVcpFeature => self.command_delay::<commands::SetVcpFeature>(),
+            Operation::SaveCurrentSettings => self.command_delay::<commands::SaveCurrentSettings>(),
+            Operation::TimingReport => self.command_delay::<commands::GetTimingReport>(),
+            Operation::Table | Operation::Command => self.command_delay::<commands::TableRead>(),
+            Operation::ReadEdid => Duration::default(),
+        }
+    }
+
+    fn scale(ms: u64, percent: u32) -> Duration {
+        Duration::from_micros(ms * percent as u64 * 10)
+    }
+}
+
+impl Display {
+    /// The delays used when communicating with the display.
+    pub fn delays(&self) -> Delays {
+        self.delays
+    }
+
+    /// Change the delays used when communicating with the display.
+    pub fn set_delays(&mut self, delays: Delays) {
+        self.delays = delays;
+    }
+
+    /// How long the next command would wait for the display to be ready.
+    ///
+    /// Async integrations can wait this out without blocking before issuing
+    /// the next command, which then won't need to sleep.
+    pub fn pending_sleep(&self) -> Duration {
+        bus::remaining(&self.bus)
+    }
+}
+
+/// Execute a command with the given delays.
+///
+/// This mirrors `ddc`'s own implementation, which always uses the specified
+/// delays.
+#[allow(dead_code)]
+pub(crate) fn execute<D: DdcCommandRawMarker, C: Command>(
+    ddc: &mut D,
+    command: C,
+    delays: &Delays,
+) -> Result<C::Ok, D::Error>
+where
+    D::Error: From<ErrorCode>,
+{
+    let mut data = [0u8; 36];
+    command.encode(&mut data)?;
+
+    let mut out = [0u8; 36 + 3];
+    let out = match C::Ok::MAX_LEN {
+        0 => &mut [],
+        len => &mut out[..len + 3],
+    };
+    let res = match ddc.execute_raw(&data[..command.len()], out, delays.response_delay::<C>()) {
+        Ok(res) => {
+            ddc.set_sleep_delay(Delay::new(delays.command_delay::<C>()));
+            res
+        },
+        Err(e) => {
+            ddc.set_sleep_delay(Delay::new(delays.failed_command_delay()));
+            return Err(e)
+        },
+    };
+
+    let res = C::Ok::decode(res);
+    if res.is_err() {
+        ddc.set_sleep_delay(Delay::new(delays.failed_command_delay()));
+    }
+
+    res.map_err(From::from)
+}
+
+/// A view of a handle that performs the high level DDC operations as
+/// individual commands, so that they can use custom delays or be dumped.
+pub(crate) struct Commands<'a> {
+    handle: &'a mut Handle,
+    delays: Delays,
+}
+
+impl<'a> Commands<'a> {
+    /// Wrap the handle if its backend can execute individual commands and
+    /// doing so would make a difference.
+    pub(crate) fn new(handle: &'a mut Handle, delays: Delays) -> Option<Self> {
+        #[cfg(feature = "ddc-dump")]
+        let needed = delays != Delays::SPEC || crate::dump::enabled();
+        #[cfg(not(feature = "ddc-dump"))]
+        let needed = delays != Delays::SPEC;
+
+        match needed && handle.supports(Operation::Command) {
+            true => Some(Commands { handle, delays }),
+            false => None,
+        }
+    }
+
+    /// Wrap the handle if its backend can execute individual commands, even
+    /// if doing so makes no difference.
+    #[cfg(feature = "mccs-db")]
+    pub(crate) fn supported(handle: &'a mut Handle, delays: Delays) -> Option<Self> {
+        match handle.supports(Operation::Command) {
+            true => Some(Commands { handle, delays }),
+            false => None,
+        }
+    }
+}
+
+impl<'a> DdcHost for Commands<'a> {
+    type Error = Error;
+
+    fn sleep(&mut self) {
+        self.handle.sleep()
+    }
+}
+
+impl<'a> DdcCommand for Commands<'a> {
+    fn execute<C: Command>(&mut self, command: C) -> Result<C::Ok, Error> {
+        self.handle.execute_with(command, &self.delays)
+    }
+}
+
+impl<'a> DdcCommandMarker for Commands<'a> {}
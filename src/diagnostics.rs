@@ -0,0 +1,53 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{Ddc, Display, FeatureCode, VcpValue},
+    log::{debug, trace},
+};
+
+/// Usage and firmware information reported by a display.
+///
+/// Each field is `None` if the display could not report it.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Diagnostics {
+    /// Accumulated power-on time in hours (`0xc0`).
+    pub usage_hours: Option<u32>,
+    /// Key identifying the application allowed to control the display
+    /// (`0xc6`).
+    pub application_key: Option<u16>,
+    /// Manufacturer of the display controller (`0xc8`).
+    pub controller_manufacturer: Option<u8>,
+    /// Manufacturer-specific display controller type (`0xc8`).
+    pub controller_type: Option<u16>,
+    /// Firmware version and revision (`0xc9`).
+    pub firmware_version: Option<(u8, u8)>,
+}
+
+impl Display {
+    /// Read the display's usage counters and firmware information.
+    ///
+    /// Features the display does not support are left unset.
+    pub fn diagnostics(&mut self) -> Diagnostics {
+        trace!("Display::diagnostics");
+
+        let usage = self.read_diagnostic(0xc0);
+        let key = self.read_diagnostic(0xc6);
+        let controller = self.read_diagnostic(0xc8);
+        let firmware = self.read_diagnostic(0xc9);
+
+        Diagnostics {
+            usage_hours: usage.map(|v| ((v.ml as u32) << 16) | v.value() as u32),
+            application_key: key.map(|v| v.value()),
+            controller_manufacturer: controller.map(|v| v.sl),
+            controller_type: controller.map(|v| ((v.ml as u16) << 8) | v.sh as u16),
+            firmware_version: firmware.map(|v| (v.sh, v.sl)),
+        }
+    }
+
+    fn read_diagnostic(&mut self, code: FeatureCode) -> Option<VcpValue> {
+        self.get_vcp_feature(code)
+            .map_err(|e| debug!("Failed to read feature 0x{:02x} of {}: {}", code, self.info, e))
+            .ok()
+    }
+}
@@ -0,0 +1,328 @@
+//! Monitor names and adapters from the Windows display configuration, which
+//! reads them from the EDID even when DDC/CI does not work, the EDID itself
+//! from the registry, and opening WinApi displays by their device name.
+
+use {
+    crate::{Backend, BackendError, Display, DisplayAdapter, DisplayInfo, Error, Handle},
+    log::{debug, trace},
+    std::{collections::BTreeMap, mem, ptr},
+    winapi::{
+        shared::{
+            basetsd::UINT32,
+            minwindef::{DWORD, TRUE},
+            ntdef::LONG,
+            windef::HMONITOR,
+            winerror::ERROR_SUCCESS,
+        },
+        um::{
+            wingdi::{
+                DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME, DISPLAYCONFIG_DEVICE_INFO_GET_TARGET_NAME,
+                DISPLAYCONFIG_DEVICE_INFO_HEADER, DISPLAYCONFIG_MODE_INFO,
+                DISPLAYCONFIG_OUTPUT_TECHNOLOGY_DISPLAYPORT_EMBEDDED,
+                DISPLAYCONFIG_OUTPUT_TECHNOLOGY_DISPLAYPORT_EXTERNAL, DISPLAYCONFIG_OUTPUT_TECHNOLOGY_DVI,
+                DISPLAYCONFIG_OUTPUT_TECHNOLOGY_HD15, DISPLAYCONFIG_OUTPUT_TECHNOLOGY_HDMI,
+                DISPLAYCONFIG_OUTPUT_TECHNOLOGY_INTERNAL, DISPLAYCONFIG_OUTPUT_TECHNOLOGY_LVDS,
+                DISPLAYCONFIG_OUTPUT_TECHNOLOGY_MIRACAST, DISPLAYCONFIG_PATH_INFO, DISPLAYCONFIG_ROTATION_IDENTITY,
+                DISPLAYCONFIG_ROTATION_ROTATE180, DISPLAYCONFIG_ROTATION_ROTATE270, DISPLAYCONFIG_ROTATION_ROTATE90,
+                DISPLAYCONFIG_SOURCE_DEVICE_NAME, DISPLAYCONFIG_TARGET_DEVICE_NAME, DISPLAYCONFIG_TOPOLOGY_ID,
+                DISPLAYCONFIG_VIDEO_OUTPUT_TECHNOLOGY, DISPLAY_DEVICEW, QDC_ONLY_ACTIVE_PATHS,
+            },
+            winreg::{RegGetValueW, HKEY_LOCAL_MACHINE, LSTATUS, RRF_RT_REG_BINARY},
+            winuser::{EnumDisplayDevicesW, GetMonitorInfoW, MONITORINFOEXW},
+        },
+    },
+};
+
+// not provided by winapi
+#[link(name = "user32")]
+extern "system" {
+    fn GetDisplayConfigBufferSizes(
+        flags: UINT32,
+        num_path_array_elements: *mut UINT32,
+        num_mode_info_array_elements: *mut UINT32,
+    ) -> LONG;
+    fn QueryDisplayConfig(
+        flags: UINT32,
+        num_path_array_elements: *mut UINT32,
+        path_array: *mut DISPLAYCONFIG_PATH_INFO,
+        num_mode_info_array_elements: *mut UINT32,
+        mode_info_array: *mut DISPLAYCONFIG_MODE_INFO,
+        current_topology_id: *mut DISPLAYCONFIG_TOPOLOGY_ID,
+    ) -> LONG;
+    fn DisplayConfigGetDeviceInfo(request_packet: *mut DISPLAYCONFIG_DEVICE_INFO_HEADER) -> LONG;
+}
+
+fn from_wide(s: &[u16]) -> String {
+    let len = s.iter().position(|&c| c == 0).unwrap_or(s.len());
+    String::from_utf16_lossy(&s[..len])
+}
+
+/// Identifying information for a monitor connected to a display source.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TargetName {
+    friendly_name: Option<String>,
+    manufacturer_id: Option<String>,
+    model_id: Option<u16>,
+    edid: Option<Vec<u8>>,
+    adapter: DisplayAdapter,
+}
+
+impl TargetName {
+    /// Fill in anything the display info is missing.
+    pub(crate) fn fill(&self, info: &mut DisplayInfo) {
+        let edid = self.edid.clone().and_then(|edid| {
+            DisplayInfo::from_edid(info.backend, info.id.clone(), edid)
+                .map_err(|e| debug!("Failed to parse the registry EDID of {}: {}", info.id, e))
+                .ok()
+        });
+        let mut target = edid.unwrap_or_else(|| DisplayInfo::new(info.backend, info.id.clone()));
+        target.model_name = self.friendly_name.clone().or(target.model_name);
+        target.manufacturer_id = self.manufacturer_id.clone();
+        target.model_id = self.model_id;
+        target.adapter = Some(self.adapter.clone());
+        info.update_from(&target);
+    }
+}
+
+/// The monitors connected to each GDI display device, such as `\\.\DISPLAY1`.
+pub(crate) fn target_names() -> BTreeMap<String, Vec<TargetName>> {
+    let descriptions = adapter_descriptions();
+    let mut names = BTreeMap::<_, Vec<_>>::new();
+    for path in active_paths() {
+        unsafe {
+            let mut source: DISPLAYCONFIG_SOURCE_DEVICE_NAME = mem::zeroed();
+            source.header._type = DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME;
+            source.header.size = mem::size_of_val(&source) as UINT32;
+            source.header.adapterId = path.sourceInfo.adapterId;
+            source.header.id = path.sourceInfo.id;
+            if DisplayConfigGetDeviceInfo(&mut source.header) != ERROR_SUCCESS as LONG {
+                continue
+            }
+
+            let mut target: DISPLAYCONFIG_TARGET_DEVICE_NAME = mem::zeroed();
+            target.header._type = DISPLAYCONFIG_DEVICE_INFO_GET_TARGET_NAME;
+            target.header.size = mem::size_of_val(&target) as UINT32;
+            target.header.adapterId = path.targetInfo.adapterId;
+            target.header.id = path.targetInfo.id;
+            if DisplayConfigGetDeviceInfo(&mut target.header) != ERROR_SUCCESS as LONG {
+                continue
+            }
+
+            let friendly_name = Some(from_wide(&target.monitorFriendlyDeviceName)).filter(|name| !name.is_empty());
+            let (manufacturer_id, model_id) = match target.flags.edidIdsValid() {
+                0 => (None, None),
+                _ => {
+                    // the id is stored as in the EDID, big-endian
+                    let id = target.edidManufactureId.swap_bytes();
+                    let letters = [(id >> 10) & 0x1f, (id >> 5) & 0x1f, id & 0x1f]
+                        .iter()
+                        .map(|&c| (b'@' + c as u8) as char)
+                        .collect();
+                    (Some(letters), Some(target.edidProductCodeId))
+                },
+            };
+
+            let device = from_wide(&source.viewGdiDeviceName);
+            let luid = path.sourceInfo.adapterId;
+            let adapter = DisplayAdapter {
+                luid: Some((luid.HighPart as u32 as u64) << 32 | luid.LowPart as u64),
+                description: descriptions.get(&device).cloned(),
+                output: Some(path.sourceInfo.id),
+                source: Some(device.clone()),
+                mirrored: false,
+                rotation: match path.targetInfo.rotation {
+                    DISPLAYCONFIG_ROTATION_IDENTITY => Some(0),
+                    DISPLAYCONFIG_ROTATION_ROTATE90 => Some(90),
+                    DISPLAYCONFIG_ROTATION_ROTATE180 => Some(180),
+                    DISPLAYCONFIG_ROTATION_ROTATE270 => Some(270),
+                    _ => None,
+                },
+                connector: connector_name(target.outputTechnology).map(Into::into),
+            };
+
+            names.entry(device).or_default().push(TargetName {
+                friendly_name,
+                manufacturer_id,
+                model_id,
+                edid: registry_edid(&from_wide(&target.monitorDevicePath)),
+                adapter,
+            });
+        }
+    }
+
+    // every target of a source shows the same image
+    for targets in names.values_mut() {
+        let mirrored = targets.len() > 1;
+        for target in targets {
+            target.adapter.mirrored = mirrored;
+        }
+    }
+
+    names
+}
+
+/// The EDID that Windows keeps in the registry for a monitor, given its device
+/// interface path, such as `\\?\DISPLAY#DEL4109#5&1a2b3c4d&0&UID4352#{...}`.
+fn registry_edid(device_path: &str) -> Option<Vec<u8>> {
+    let mut parts = device_path.strip_prefix(r"\\?\")?.split('#');
+    let (class, id, instance) = (parts.next()?, parts.next()?, parts.next()?);
+    let key = format!(
+        r"SYSTEM\CurrentControlSet\Enum\{}\{}\{}\Device Parameters",
+        class, id, instance
+    );
+    let key: Vec<u16> = key.encode_utf16().chain(Some(0)).collect();
+    let value: Vec<u16> = "EDID".encode_utf16().chain(Some(0)).collect();
+
+    unsafe {
+        let read = |data: *mut u8, len: &mut DWORD| {
+            RegGetValueW(
+                HKEY_LOCAL_MACHINE,
+                key.as_ptr(),
+                value.as_ptr(),
+                RRF_RT_REG_BINARY,
+                ptr::null_mut(),
+                data as *mut _,
+                len,
+            ) == ERROR_SUCCESS as LSTATUS
+        };
+
+        let mut len = 0;
+        if !read(ptr::null_mut(), &mut len) {
+            return None
+        }
+        let mut edid = vec![0; len as usize];
+        if !read(edid.as_mut_ptr(), &mut len) {
+            return None
+        }
+        edid.truncate(len as usize);
+        Some(edid).filter(|edid| !edid.is_empty())
+    }
+}
+
+fn active_paths() -> Vec<DISPLAYCONFIG_PATH_INFO> {
+    unsafe {
+        let (mut num_paths, mut num_modes) = (0, 0);
+        if GetDisplayConfigBufferSizes(QDC_ONLY_ACTIVE_PATHS, &mut num_paths, &mut num_modes) != ERROR_SUCCESS as LONG {
+            return Vec::new()
+        }
+
+        let mut paths = vec![mem::zeroed(); num_paths as usize];
+        let mut modes = vec![mem::zeroed(); num_modes as usize];
+        if QueryDisplayConfig(
+            QDC_ONLY_ACTIVE_PATHS,
+            &mut num_paths,
+            paths.as_mut_ptr(),
+            &mut num_modes,
+            modes.as_mut_ptr(),
+            ptr::null_mut(),
+        ) != ERROR_SUCCESS as LONG
+        {
+            return Vec::new()
+        }
+
+        paths.truncate(num_paths as usize);
+        paths
+    }
+}
+
+fn connector_name(technology: DISPLAYCONFIG_VIDEO_OUTPUT_TECHNOLOGY) -> Option<&'static str> {
+    Some(match technology {
+        DISPLAYCONFIG_OUTPUT_TECHNOLOGY_HD15 => "VGA",
+        DISPLAYCONFIG_OUTPUT_TECHNOLOGY_DVI => "DVI",
+        DISPLAYCONFIG_OUTPUT_TECHNOLOGY_HDMI => "HDMI",
+        DISPLAYCONFIG_OUTPUT_TECHNOLOGY_LVDS => "LVDS",
+        DISPLAYCONFIG_OUTPUT_TECHNOLOGY_DISPLAYPORT_EXTERNAL => "DisplayPort",
+        DISPLAYCONFIG_OUTPUT_TECHNOLOGY_DISPLAYPORT_EMBEDDED => "eDP",
+        DISPLAYCONFIG_OUTPUT_TECHNOLOGY_MIRACAST => "Miracast",
+        DISPLAYCONFIG_OUTPUT_TECHNOLOGY_INTERNAL => "Internal",
+        _ => return None,
+    })
+}
+
+/// The description of the adapter behind each GDI display device.
+fn adapter_descriptions() -> BTreeMap<String, String> {
+    let mut descriptions = BTreeMap::new();
+    for i in 0.. {
+        unsafe {
+            let mut device: DISPLAY_DEVICEW = mem::zeroed();
+            device.cb = mem::size_of_val(&device) as _;
+            if EnumDisplayDevicesW(ptr::null(), i, &mut device, 0) != TRUE {
+                break
+            }
+            descriptions.insert(from_wide(&device.DeviceName), from_wide(&device.DeviceString));
+        }
+    }
+
+    descriptions
+}
+
+/// The GDI display device name of a monitor.
+pub(crate) fn monitor_device(monitor: HMONITOR) -> Option<String> {
+    unsafe {
+        let mut info: MONITORINFOEXW = mem::zeroed();
+        info.cbSize = mem::size_of_val(&info) as _;
+        match GetMonitorInfoW(monitor, &mut info as *mut _ as *mut _) {
+            TRUE => Some(from_wide(&info.szDevice)),
+            _ => None,
+        }
+    }
+}
+
+impl Display {
+    /// Open a WinApi display by its device name, such as
+    /// `\\.\DISPLAY2\Monitor0`.
+    ///
+    /// Only the monitors attached to the named display source are opened, so
+    /// this is much cheaper than enumerating every display.
+    pub fn open_winapi(device_name: &str) -> Result<Self, Error> {
+        trace!("Display::open_winapi({})", device_name);
+
+        let not_found = || Error::InvalidArgument(format!("no WinApi display named {}", device_name));
+        let (source, index) = device_name
+            .rsplit_once('\\')
+            .and_then(|(source, monitor)| {
+                let index = monitor.to_ascii_lowercase().strip_prefix("monitor")?.parse().ok()?;
+                Some((source, index))
+            })
+            .ok_or_else(|| Error::InvalidArgument(format!("malformed WinApi device name {:?}", device_name)))?;
+
+        let monitor = ddc_winapi::enumerate_monitors()
+            .map_err(BackendError::WinApiError)?
+            .into_iter()
+            .find(|&monitor| monitor_device(monitor).is_some_and(|device| device.eq_ignore_ascii_case(source)))
+            .ok_or_else(not_found)?;
+        let physical = ddc_winapi::get_physical_monitors_from_hmonitor(monitor).map_err(BackendError::WinApiError)?;
+        let count = physical.len();
+        // every handle must be wrapped so that the unused ones are released
+        let mut monitors: Vec<_> = physical
+            .into_iter()
+            .map(|monitor| unsafe { ddc_winapi::Monitor::new(monitor) })
+            .collect();
+        if index >= count {
+            return Err(not_found())
+        }
+        let ddc = monitors.swap_remove(index);
+
+        let mut info = DisplayInfo::new(Backend::WinApi, ddc.description());
+        let targets = target_names()
+            .into_iter()
+            .find(|(device, _)| device.eq_ignore_ascii_case(source))
+            .map(|(_, targets)| targets)
+            .unwrap_or_default();
+        if targets.len() == count {
+            targets[index].fill(&mut info);
+        }
+
+        Ok(Display::from_handle(Handle::WinApi(ddc), info))
+    }
+
+    /// Wrap a physical monitor that was found elsewhere.
+    ///
+    /// WinApi monitors can't read their EDID, so the caller provides what is
+    /// known about the display.
+    pub fn from_winapi(monitor: ddc_winapi::Monitor, info: DisplayInfo) -> Self {
+        trace!("Display::from_winapi({})", info);
+
+        Display::from_handle(Handle::WinApi(monitor), info)
+    }
+}
@@ -0,0 +1,220 @@
+//! Linux DRM connectors, found through sysfs, which name the output an I2C
+//! bus belongs to and keep a copy of the EDID of the display connected to it,
+//! and the filtering of I2C buses that can't have a display attached.
+
+use {
+    crate::{Backend, Display, DisplayAdapter, DisplayInfo},
+    ddc::Edid,
+    log::{debug, trace},
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        fs,
+        os::unix::fs::MetadataExt,
+        path::{Path, PathBuf},
+        sync::atomic::{AtomicBool, Ordering},
+    },
+};
+
+const DRM_CLASS: &str = "/sys/class/drm";
+const I2C_DEV_CLASS: &str = "/sys/class/i2c-dev";
+
+static FILTER_BUSES: AtomicBool = AtomicBool::new(true);
+
+/// A DRM connector, such as `card0-HDMI-A-1`.
+#[derive(Clone, Debug)]
+pub(crate) struct Connector {
+    name: String,
+    path: PathBuf,
+}
+
+impl Connector {
+    /// The EDID of the connected display, as read by the kernel.
+    pub(crate) fn edid(&self) -> Option<Vec<u8>> {
+        fs::read(self.path.join("edid")).ok().filter(|edid| !edid.is_empty())
+    }
+
+    /// The I2C buses of the connector's DisplayPort AUX channels.
+    fn aux_buses(&self) -> impl Iterator<Item = u32> {
+        fs::read_dir(&self.path)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| parse_i2c_bus(&entry.file_name().to_string_lossy()))
+    }
+
+    /// Identify the display by its connector rather than its device number.
+    pub(crate) fn fill(&self, info: &mut DisplayInfo) {
+        info.id = self.name.clone();
+        info.drm_connector = Some(self.name.clone());
+
+        // card0-HDMI-A-1 is an HDMI-A connector
+        let kind = self
+            .name
+            .split_once('-')
+            .and_then(|(_, name)| name.rsplit_once('-'))
+            .map(|(kind, _)| kind.to_owned());
+        let adapter = info.adapter.get_or_insert_with(DisplayAdapter::default);
+        if adapter.connector.is_none() {
+            adapter.connector = kind;
+        }
+    }
+}
+
+/// Identify an i2c-dev display from its EDID.
+///
+/// Some drivers block EDID reads over I2C, so the kernel's copy of the EDID
+/// is used instead if it can't be read or parsed.
+pub(crate) fn display_info(
+    ddc: &mut ddc_i2c::I2cDeviceDdc,
+    rdev: u64,
+    connector: Option<&Connector>,
+) -> Result<DisplayInfo, String> {
+    let id = rdev.to_string();
+    let mut edid = vec![0u8; 0x100];
+    let res = ddc
+        .read_edid(0, &mut edid)
+        .map_err(|e| format!("failed to read EDID for i2c-{}: {}", id, e))
+        .and_then(|_| {
+            DisplayInfo::from_edid(Backend::I2cDevice, id.clone(), edid)
+                .map_err(|e| format!("failed to parse EDID for i2c-{}: {}", id, e))
+        });
+    let mut info = match (res, connector.and_then(Connector::edid)) {
+        (Ok(info), _) => info,
+        (Err(e), Some(edid)) => {
+            debug!("Using the sysfs EDID instead: {}", e);
+            DisplayInfo::from_edid(Backend::I2cDevice, id.clone(), edid)
+                .map_err(|e| format!("failed to parse sysfs EDID for i2c-{}: {}", id, e))?
+        },
+        (Err(e), None) => return Err(e),
+    };
+
+    if let Some(connector) = connector {
+        connector.fill(&mut info);
+    }
+    Ok(info)
+}
+
+/// The bus number of an I2C device, from its device number.
+pub(crate) fn i2c_bus(rdev: u64) -> u32 {
+    (((rdev >> 12) & 0xfff00) | (rdev & 0xff)) as u32
+}
+
+fn parse_i2c_bus(name: &str) -> Option<u32> {
+    name.strip_prefix("i2c-")?.parse().ok()
+}
+
+/// The I2C bus used to reach the display on each connector.
+///
+/// Connectors link to their DDC bus, and DisplayPort connectors also
+/// contain the I2C bus of their AUX channel. Both reach the same display, so
+/// the DDC bus is preferred and the AUX bus is only used for connectors
+/// without one. Each monitor behind a DisplayPort MST hub gets a connector
+/// of its own, with an I2C bus that the kernel routes through the hub, so
+/// they can be addressed individually.
+pub(crate) fn connectors() -> BTreeMap<u32, Connector> {
+    let mut connectors = BTreeMap::new();
+    let entries = match fs::read_dir(DRM_CLASS) {
+        Ok(entries) => entries,
+        Err(_) => return connectors,
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        // cards and render nodes don't have a connector name
+        if !name.starts_with("card") || !name.contains('-') {
+            continue
+        }
+
+        let path = entry.path();
+        let connector = Connector { name, path };
+        let ddc = fs::read_link(connector.path.join("ddc"))
+            .ok()
+            .and_then(|ddc| ddc.file_name().and_then(|name| parse_i2c_bus(&name.to_string_lossy())));
+        if let Some(bus) = ddc.or_else(|| connector.aux_buses().next()) {
+            connectors.insert(bus, connector);
+        }
+    }
+
+    connectors
+}
+
+/// Whether an I2C bus belongs to a graphics card, either through a connector
+/// or by being a child of a PCI display controller.
+fn is_display_bus(bus: u32, connectors: &BTreeMap<u32, Connector>) -> bool {
+    if connectors.contains_key(&bus) {
+        return true
+    }
+
+    let device = match fs::canonicalize(Path::new(I2C_DEV_CLASS).join(format!("i2c-{}", bus)).join("device")) {
+        Ok(device) => device,
+        Err(_) => return false,
+    };
+    device.ancestors().skip(1).any(|parent| {
+        fs::read_to_string(parent.join("class"))
+            .map(|class| class.trim().starts_with("0x03"))
+            .unwrap_or(false)
+    })
+}
+
+/// Open the I2C buses that may have a display attached.
+///
+/// Unless filtering is disabled with `Display::set_i2c_filter`, buses that
+/// don't belong to a graphics card, such as SMBus controllers, are skipped
+/// without being probed.
+pub(crate) fn devices(connectors: &BTreeMap<u32, Connector>) -> Box<dyn Iterator<Item = ddc_i2c::I2cDeviceDdc>> {
+    // the other buses of a connector would find its display a second time
+    let aliases: BTreeSet<_> = connectors
+        .iter()
+        .flat_map(|(&bus, connector)| connector.aux_buses().filter(move |&aux| aux != bus))
+        .collect();
+
+    if !FILTER_BUSES.load(Ordering::Relaxed) {
+        return Box::new(
+            ddc_i2c::I2cDeviceEnumerator::new()
+                .into_iter()
+                .flatten()
+                .filter(move |ddc| {
+                    let rdev = ddc.inner_ref().inner_ref().metadata().map(|meta| meta.rdev());
+                    rdev.map_or(true, |rdev| !aliases.contains(&i2c_bus(rdev)))
+                }),
+        )
+    }
+
+    let mut buses: Vec<_> = fs::read_dir(I2C_DEV_CLASS)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| parse_i2c_bus(&entry.file_name().to_string_lossy()))
+        .filter(|bus| !aliases.contains(bus))
+        .filter(|&bus| {
+            let display = is_display_bus(bus, connectors);
+            if !display {
+                trace!("Skipping i2c-{}, it doesn't belong to a graphics card", bus);
+            }
+            display
+        })
+        .collect();
+    buses.sort_unstable();
+
+    // only connected displays answer, but the kernel may still have their EDID
+    // if the driver blocks reading it over I2C
+    let has_edid: Vec<_> = buses
+        .iter()
+        .map(|bus| connectors.get(bus).is_some_and(|connector| connector.edid().is_some()))
+        .collect();
+    Box::new(buses.into_iter().zip(has_edid).filter_map(|(bus, has_edid)| {
+        let mut ddc = ddc_i2c::from_i2c_device(format!("/dev/i2c-{}", bus)).ok()?;
+        (has_edid || ddc.read_edid(0, &mut [0u8]).is_ok()).then_some(ddc)
+    }))
+}
+
+impl Display {
+    /// Change whether enumeration skips I2C buses that don't belong to a
+    /// graphics card.
+    ///
+    /// Filtering is enabled by default. Disable it to find displays on other
+    /// buses, such as those of USB adapters, at the cost of probing every bus.
+    pub fn set_i2c_filter(enabled: bool) {
+        FILTER_BUSES.store(enabled, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,139 @@
+//! Raw DDC/CI transaction dumps for debugging.
+//!
+//! While a sink is installed, the request and reply payload of every DDC/CI
+//! command sent to a display is passed to it. Payloads exclude the packet
+//! address, length and checksum, so a request begins with its opcode. Backends
+//! such as WinAPI that don't expose individual commands are not dumped.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! ddc_hi::dump::set_sink(Some(Box::new(|record| eprintln!("{}", record))));
+//! ```
+
+use {
+    crate::{Delays, Error, Handle},
+    ddc::{
+        commands::{Command, CommandResult},
+        ErrorCode,
+    },
+    std::{
+        fmt,
+        sync::Mutex,
+        time::{SystemTime, UNIX_EPOCH},
+    },
+};
+
+/// A single dumped DDC/CI transaction.
+#[derive(Debug)]
+pub struct DumpRecord<'a> {
+    /// When the request was sent.
+    pub timestamp: SystemTime,
+    /// The request payload.
+    pub request: &'a [u8],
+    /// The reply payload, if one was received.
+    pub response: Option<&'a [u8]>,
+    /// The error the transaction failed with, if any.
+    pub error: Option<&'a Error>,
+}
+
+impl<'a> fmt::Display for DumpRecord<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let timestamp = self.timestamp.duration_since(UNIX_EPOCH).unwrap_or_default();
+        write!(f, "[{}.{:06}] >", timestamp.as_secs(), timestamp.subsec_micros())?;
+        for b in self.request {
+            write!(f, " {:02x}", b)?;
+        }
+        if let Some(response) = self.response {
+            write!(f, " <")?;
+            for b in response {
+                write!(f, " {:02x}", b)?;
+            }
+        }
+        if let Some(error) = self.error {
+            write!(f, " ! {}", error)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A destination for dumped transactions.
+pub type DumpSink = Box<dyn FnMut(&DumpRecord) + Send>;
+
+static SINK: Mutex<Option<DumpSink>> = Mutex::new(None);
+
+/// Install a sink that receives every subsequent transaction, or remove it.
+pub fn set_sink(sink: Option<DumpSink>) {
+    *SINK.lock().unwrap_or_else(|e| e.into_inner()) = sink;
+}
+
+pub(crate) fn enabled() -> bool {
+    SINK.lock().map(|sink| sink.is_some()).unwrap_or(false)
+}
+
+fn dump(record: &DumpRecord) {
+    if let Some(sink) = SINK.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        sink(record)
+    }
+}
+
+struct Dump<C>(C);
+
+impl<C: Command> Command for Dump<C> {
+    type Ok = Dumped<C::Ok>;
+
+    const DELAY_COMMAND_MS: u64 = C::DELAY_COMMAND_MS;
+    const DELAY_RESPONSE_MS: u64 = C::DELAY_RESPONSE_MS;
+    const MAX_LEN: usize = C::MAX_LEN;
+    const MIN_LEN: usize = C::MIN_LEN;
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn encode(&self, data: &mut [u8]) -> Result<usize, ErrorCode> {
+        self.0.encode(data)
+    }
+}
+
+struct Dumped<R> {
+    raw: Vec<u8>,
+    value: Result<R, ErrorCode>,
+}
+
+impl<R: CommandResult> CommandResult for Dumped<R> {
+    const MAX_LEN: usize = R::MAX_LEN;
+
+    fn decode(data: &[u8]) -> Result<Self, ErrorCode> {
+        Ok(Dumped {
+            raw: data.to_owned(),
+            value: R::decode(data),
+        })
+    }
+}
+
+/// Execute a command on the handle, dumping it if a sink is installed.
+pub(crate) fn execute<C: Command>(handle: &mut Handle, command: C, delays: &Delays) -> Result<C::Ok, Error> {
+    if !enabled() {
+        return handle.execute_backend(command, delays)
+    }
+
+    let timestamp = SystemTime::now();
+    let mut request = [0u8; 36];
+    let len = command.encode(&mut request).unwrap_or(0);
+    let request = &request[..len];
+
+    let (response, res) = match handle.execute_backend(Dump(command), delays) {
+        Ok(Dumped { raw, value }) => (Some(raw), value.map_err(Error::InvalidResponse)),
+        Err(e) => (None, Err(e)),
+    };
+    dump(&DumpRecord {
+        timestamp,
+        request,
+        response: response.as_deref().filter(|_| C::Ok::MAX_LEN > 0),
+        error: res.as_ref().err(),
+    });
+
+    res
+}
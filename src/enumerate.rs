@@ -0,0 +1,70 @@
+use {
+    crate::{warning, Backend, Display},
+    log::trace,
+    std::{error, fmt},
+};
+
+/// A display or backend that failed to enumerate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnumerationError {
+    /// The backend that failed.
+    pub backend: Backend,
+    /// What went wrong.
+    pub message: String,
+}
+
+impl fmt::Display for EnumerationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.backend, self.message)
+    }
+}
+
+/// The failures encountered by `Display::try_enumerate`, along with the
+/// displays that were found regardless.
+pub struct EnumerationErrors {
+    /// The displays that were enumerated successfully.
+    pub displays: Vec<Display>,
+    /// Each backend or display that failed, in the order they occurred.
+    pub errors: Vec<EnumerationError>,
+}
+
+impl fmt::Debug for EnumerationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("EnumerationErrors")
+            .field(
+                "displays",
+                &self.displays.iter().map(|display| &display.info).collect::<Vec<_>>(),
+            )
+            .field("errors", &self.errors)
+            .finish()
+    }
+}
+
+impl fmt::Display for EnumerationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "failed to enumerate {} display(s) or backend(s)", self.errors.len())?;
+        for e in &self.errors {
+            write!(f, "; {}", e)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl error::Error for EnumerationErrors {}
+
+impl Display {
+    /// Enumerate all detected displays, failing if any backend or display
+    /// could not be enumerated.
+    ///
+    /// Unlike `Display::enumerate`, which only logs failures, this reports
+    /// every failure along with the displays that were found.
+    pub fn try_enumerate() -> Result<Vec<Self>, EnumerationErrors> {
+        trace!("Display::try_enumerate");
+
+        match warning::collect(Self::enumerate) {
+            (displays, errors) if errors.is_empty() => Ok(displays),
+            (displays, errors) => Err(EnumerationErrors { displays, errors }),
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//! Overrides that end users can set in the environment, without the
+//! application having to expose them as options.
+
+use {
+    crate::{warning, Backend, Delays, Display},
+    std::{env, str::FromStr},
+};
+
+/// A comma-separated list of the backends to enumerate, such as `i2c-dev`.
+const BACKENDS: &str = "DDC_HI_BACKENDS";
+
+/// The DDC/CI delays as a percentage of those the specification requires.
+const DELAYS: &str = "DDC_HI_DELAY_PERCENT";
+
+/// The number of attempts made at each operation.
+const ATTEMPTS: &str = "DDC_HI_ATTEMPTS";
+
+fn var<T: FromStr>(name: &str) -> Option<T> {
+    let value = env::var(name).ok()?;
+    match value.trim().parse() {
+        Ok(value) => Some(value),
+        Err(_) => {
+            warning::warning(format!("Ignoring invalid {}={:?}", name, value));
+            None
+        },
+    }
+}
+
+/// Whether the backend should be enumerated.
+pub(crate) fn backend_enabled(backend: Backend) -> bool {
+    let backends = match env::var(BACKENDS) {
+        Ok(backends) => backends,
+        Err(_) => return true,
+    };
+
+    backends.split(',').map(str::trim).any(|name| name == backend.name())
+}
+
+/// Apply the overrides to a newly enumerated display.
+pub(crate) fn apply(mut display: Display) -> Display {
+    if let Some(percent) = var(DELAYS) {
+        display.set_delays(Delays::scaled(percent));
+    }
+    if let Some(attempts) = var(ATTEMPTS) {
+        let policy = display.retry_policy().clone().max_attempts(attempts);
+        display.set_retry_policy(policy);
+    }
+
+    display
+}
@@ -0,0 +1,27 @@
+use crate::{Backend, Ddc, DdcTable, Display, DisplayInfo, Edid, Error};
+
+/// A display that can be stored as a trait object alongside displays of
+/// other types, such as mocks in tests.
+///
+/// `Display` implements this trait, and `Box<dyn DdcDisplay + Send>` can be
+/// used where the displays must be moved between threads.
+pub trait DdcDisplay: Ddc<Error = Error> + DdcTable + Edid<EdidError = Error> {
+    /// Information about the display.
+    fn info(&self) -> &DisplayInfo;
+
+    /// Identifies the backend or driver used to communicate with the display.
+    fn backend(&self) -> Backend {
+        self.info().backend
+    }
+
+    /// A unique identifier for the display, format is specific to the backend.
+    fn id(&self) -> &str {
+        &self.info().id
+    }
+}
+
+impl DdcDisplay for Display {
+    fn info(&self) -> &DisplayInfo {
+        &self.info
+    }
+}
@@ -0,0 +1,59 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{Diagnostics, Display, Metrics, Stats, TimingReport},
+    log::{debug, trace},
+};
+
+/// The size of an EDID block, each of which ends in a checksum byte.
+const EDID_BLOCK_LEN: usize = 128;
+
+/// An aggregate of everything a display reports about its own state, as
+/// produced by `Display::health`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Health {
+    /// The display's timing report, if it could be read.
+    pub timing: Option<TimingReport>,
+    /// Usage counters and firmware information.
+    pub diagnostics: Diagnostics,
+    /// Whether every block of the EDID has a valid checksum, if the EDID is
+    /// known.
+    pub edid_checksum_valid: Option<bool>,
+    /// Operation statistics for this display, if collection is enabled.
+    pub metrics: Option<Metrics>,
+    /// Operation statistics for all displays.
+    pub stats: Stats,
+}
+
+impl Display {
+    /// Gather the display's timing, diagnostics, EDID checksum status and
+    /// error statistics into a single report.
+    ///
+    /// Anything the display fails to report is left unset.
+    pub fn health(&mut self) -> Health {
+        trace!("Display::health");
+
+        let timing = self
+            .timing()
+            .map_err(|e| debug!("Failed to read timing report of {}: {}", self.info, e))
+            .ok();
+        let diagnostics = self.diagnostics();
+
+        Health {
+            timing,
+            diagnostics,
+            edid_checksum_valid: self.info.edid_data.as_deref().map(edid_checksum_valid),
+            metrics: self.metrics.clone(),
+            stats: Stats::snapshot(),
+        }
+    }
+}
+
+fn edid_checksum_valid(edid: &[u8]) -> bool {
+    !edid.is_empty()
+        && edid.len() % EDID_BLOCK_LEN == 0
+        && edid
+            .chunks(EDID_BLOCK_LEN)
+            .all(|block| block.iter().fold(0u8, |sum, &b| sum.wrapping_add(b)) == 0)
+}
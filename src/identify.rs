@@ -0,0 +1,34 @@
+use {
+    crate::{Ddc, Display, Error, FeatureCode},
+    log::trace,
+    std::{thread, time::Duration},
+};
+
+/// The feature perturbed to identify a display.
+const IDENTIFY_FEATURE: FeatureCode = 0x10;
+
+/// How long the display stays perturbed.
+const IDENTIFY_DURATION: Duration = Duration::from_millis(750);
+
+impl Display {
+    /// Make the display visibly identify itself.
+    ///
+    /// This briefly changes the display's luminance (`0x10`) by a quarter of
+    /// its range and then restores it, so that users can tell which physical
+    /// display this is.
+    pub fn identify(&mut self) -> Result<(), Error> {
+        trace!("Display::identify");
+
+        let current = self.get_vcp_feature(IDENTIFY_FEATURE)?;
+        let (value, maximum) = (current.value(), current.maximum());
+        let delta = (maximum / 4).max(1);
+        let perturbed = match value > maximum / 2 {
+            true => value.saturating_sub(delta),
+            false => value.saturating_add(delta).min(maximum),
+        };
+
+        self.set_vcp_feature(IDENTIFY_FEATURE, perturbed)?;
+        thread::sleep(IDENTIFY_DURATION);
+        self.set_vcp_feature(IDENTIFY_FEATURE, value)
+    }
+}
@@ -0,0 +1,80 @@
+//! Switch the input source of several displays at once, as a KVM switch
+//! would.
+//!
+//! Displays usually stop responding to DDC/CI once they switch to another
+//! computer, so a switch that isn't acknowledged is assumed to have
+//! succeeded rather than being reported as a failure.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use ddc_hi::{kvm, Query};
+//!
+//! // switch every display to DisplayPort 1
+//! let report = kvm::switch_input(&Query::Any, 0x0f, true);
+//! for (info, error) in report.failures() {
+//!     eprintln!("{}: {}", info, error);
+//! }
+//! ```
+
+use {
+    crate::{broadcast::BroadcastReport, Ddc, Display, Error, FeatureCode, Query},
+    log::{debug, trace},
+    std::thread,
+};
+
+/// The input source feature.
+const INPUT_SOURCE: FeatureCode = 0x60;
+
+/// Switch the input source (`0x60`) of every display that matches `query`.
+///
+/// Displays already on `input` are left alone. Unless `parallel` is set,
+/// each switch waits for its display's settle time before the next display
+/// is switched, for setups where switching one display disturbs the others.
+pub fn switch_input(query: &Query, input: u16, parallel: bool) -> BroadcastReport {
+    trace!("kvm::switch_input({:?}, 0x{:02x}, {})", query, input, parallel);
+
+    let displays: Vec<_> = Display::enumerate()
+        .into_iter()
+        .filter(|display| query.matches(&display.info))
+        .collect();
+
+    let count = displays.len();
+    let results = displays
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut display)| {
+            let res = switch(&mut display, input);
+            if !parallel && i + 1 < count && matches!(res, Ok(true)) {
+                if let Some(settle) = display.settle_time(INPUT_SOURCE) {
+                    thread::sleep(settle);
+                }
+            }
+            (display.info, res.map(drop))
+        })
+        .collect();
+
+    BroadcastReport { results }
+}
+
+/// Switch a single display, returning whether it needed switching.
+fn switch(display: &mut Display, input: u16) -> Result<bool, Error> {
+    match display.get_vcp_feature(INPUT_SOURCE) {
+        Ok(value) if value.value() == input => {
+            debug!("{} is already on input 0x{:02x}", display.info, input);
+            return Ok(false)
+        },
+        Ok(_) => (),
+        Err(e) => debug!("Failed to read the input source of {}: {}", display.info, e),
+    }
+
+    // the display won't come back if it switched away, so don't wait for it
+    match display.set_vcp_feature(INPUT_SOURCE, input) {
+        Ok(()) => Ok(true),
+        Err(e) if e.is_transient() => {
+            debug!("Assuming {} switched to input 0x{:02x}: {}", display.info, input, e);
+            Ok(true)
+        },
+        Err(e) => Err(e),
+    }
+}
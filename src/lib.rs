@@ -21,14 +21,134 @@
 //! }
 //! ```
 
-pub use ddc::{Ddc, DdcHost, DdcTable, FeatureCode, TimingMessage, VcpValue, VcpValueType};
+#[cfg(feature = "unsafe-raw")]
+pub use raw::RAW_PAYLOAD_MAX_LEN;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+pub use {
+    asset::ASSET_TAG_MAX_LEN,
+    builder::DisplayInfoBuilder,
+    ddc::{Ddc, DdcHost, DdcTable, Edid, FeatureCode, TimingMessage, VcpValue, VcpValueType},
+    delays::Delays,
+    diagnostics::Diagnostics,
+    enumerate::{EnumerationError, EnumerationErrors},
+    erased::DdcDisplay,
+    health::Health,
+    lut::{decode_lut, encode_lut, LutSize},
+    manufacture::ManufactureDate,
+    metrics::{Metrics, OperationMetrics, Stats},
+    orientation::Orientation,
+    picture::{DisplayApplication, DISPLAY_APPLICATION},
+    profile::{Profile, ProfileChange},
+    reset::Confirm,
+    retry::RetryPolicy,
+    summary::DisplaySummary,
+    timing::TimingReport,
+    transaction::{TransactionError, VcpTransaction},
+    update::{IdentityCheck, UpdateLevel},
+    warning::WarningSink,
+    watch::{FeatureChange, FeatureWatcher},
+};
+#[cfg(feature = "mccs-db")]
+pub use {
+    audit::{AuditFinding, AuditIssue},
+    database::{FeatureAccess, FeatureDescription, FeatureKind},
+    locale::Translations,
+    pretty::FeatureTable,
+    report::{DisplayReport, FeatureReport, FeatureState},
+};
 use {
-    ddc::Edid,
-    log::{trace, warn},
-    std::{fmt, io, iter::FromIterator, str},
+    blacklist::Blacklist,
+    cache::ValueCache,
+    ddc::commands::Command,
+    delays::Commands,
+    log::{info, trace, warn},
+    std::{
+        collections::BTreeMap,
+        fmt, io, iter, str,
+        time::{Duration, Instant},
+    },
     thiserror::Error,
 };
 
+pub mod ambient;
+mod asset;
+#[cfg(feature = "mccs-db")]
+mod audit;
+mod blacklist;
+pub mod brightness;
+pub mod broadcast;
+#[cfg(feature = "broker")]
+pub mod broker;
+mod builder;
+mod bus;
+mod cache;
+#[cfg(feature = "mccs-db")]
+mod caps;
+#[cfg(feature = "mccs-db")]
+mod database;
+pub mod ddcutil;
+mod delays;
+mod diagnostics;
+#[cfg(feature = "has-ddc-winapi")]
+mod display_config;
+#[cfg(feature = "has-ddc-i2c")]
+mod drm;
+#[cfg(feature = "ddc-dump")]
+pub mod dump;
+mod enumerate;
+mod env;
+mod erased;
+mod health;
+mod identify;
+pub mod kvm;
+#[cfg(feature = "mccs-db")]
+mod locale;
+mod lut;
+#[cfg(feature = "has-ddc-macos")]
+mod macos;
+mod manufacture;
+mod metrics;
+#[cfg(feature = "has-nvapi")]
+mod nvidia;
+mod orientation;
+mod permission;
+mod picture;
+#[cfg(all(feature = "broker", feature = "has-ddc-winapi"))]
+mod pipe;
+#[cfg(feature = "pnp-ids")]
+pub mod pnp;
+#[cfg(feature = "mccs-db")]
+mod pretty;
+mod profile;
+#[cfg(feature = "mccs-db")]
+pub mod profiles;
+#[cfg(feature = "unsafe-raw")]
+mod raw;
+mod ready;
+mod redact;
+#[cfg(feature = "mccs-db")]
+mod report;
+mod reset;
+mod retry;
+#[cfg(feature = "schedule")]
+pub mod schedule;
+mod settle;
+#[cfg(feature = "tracing")]
+mod spans;
+mod summary;
+pub mod sync;
+mod table;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod timing;
+mod transaction;
+mod update;
+#[cfg(feature = "vendor")]
+pub mod vendor;
+mod warning;
+mod watch;
+
 /// The error type for high level DDC/CI monitor operations.
 #[derive(Debug, Error)]
 pub enum Error {
@@ -47,6 +167,50 @@ pub enum Error {
     /// Low level errors.
     #[error("low level error: {0}")]
     LowLevelError(#[from] BackendError),
+
+    /// An argument was not valid for the requested operation.
+    #[error("invalid argument: {0}")]
+    InvalidArgument(String),
+
+    /// The display responded with data that does not match the request.
+    #[error("invalid response: {0}")]
+    InvalidResponse(ddc::ErrorCode),
+
+    /// The operation was refused because it was attempted too soon after
+    /// the previous one.
+    #[error("operation rate limited, retry in {0:?}")]
+    RateLimited(Duration),
+
+    /// A value read back after a write did not match what was written.
+    #[error("feature 0x{code:02x} reads back as {actual} after writing {expected}")]
+    VerificationFailed {
+        /// The feature that was written.
+        code: FeatureCode,
+        /// The value that was written.
+        expected: u16,
+        /// The value that was read back.
+        actual: u16,
+    },
+
+    /// Access to a display was denied by the operating system.
+    #[error("permission denied for {path}: {hint}")]
+    PermissionDenied {
+        /// The device or display that could not be accessed.
+        path: String,
+        /// A suggestion for how to gain access.
+        hint: &'static str,
+    },
+
+    /// An error that occurred while communicating with a particular display.
+    #[error("{backend} display {id}: {source}")]
+    DisplayError {
+        /// The backend of the display.
+        backend: Backend,
+        /// The backend-specific identifier of the display.
+        id: String,
+        /// The underlying error.
+        source: Box<Error>,
+    },
 }
 
 /// A wrapper for the DDC backend errors.
@@ -73,6 +237,270 @@ pub enum BackendError {
     /// Nvapi error.
     #[error("nvapi error: {0}")]
     NvapiError(ddc_i2c::Error<nvapi::Status>),
+
+    #[cfg(target_os = "linux")]
+    /// Linux backlight error.
+    #[error("backlight error: {0}")]
+    BacklightError(io::Error),
+}
+
+/// A coarse classification of an `Error`, independent of the backend that
+/// produced it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The display did not acknowledge the request, or replied with a null
+    /// message.
+    Nak,
+    /// A reply was corrupted in transmission.
+    Checksum,
+    /// The operation timed out.
+    Timeout,
+    /// Access to the display or bus was denied.
+    PermissionDenied,
+    /// The display or bus is no longer present.
+    DeviceGone,
+    /// The backend does not support the operation.
+    Unsupported,
+    /// The display does not support the requested VCP feature.
+    UnsupportedFeature,
+    /// The display replied with unexpected or malformed data.
+    InvalidResponse,
+    /// An argument was not valid for the requested operation.
+    InvalidArgument,
+    /// The operation was refused because it was attempted too soon.
+    RateLimited,
+    /// A value read back after a write did not match.
+    VerificationFailed,
+    /// Any other error.
+    Other,
+}
+
+impl ErrorKind {
+    /// Whether an operation that failed with this kind of error may succeed
+    /// if retried.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            *self,
+            ErrorKind::Nak
+                | ErrorKind::Checksum
+                | ErrorKind::Timeout
+                | ErrorKind::InvalidResponse
+                | ErrorKind::RateLimited
+        )
+    }
+
+    fn from_ddc(code: &ddc::ErrorCode) -> Self {
+        match *code {
+            ddc::ErrorCode::InvalidChecksum => ErrorKind::Checksum,
+            // ddc reports a null message as an invalid length
+            ddc::ErrorCode::InvalidLength => ErrorKind::Nak,
+            ddc::ErrorCode::Invalid(ref msg) if msg == "Unsupported VCP code" => ErrorKind::UnsupportedFeature,
+            _ => ErrorKind::InvalidResponse,
+        }
+    }
+
+    #[allow(dead_code)]
+    fn from_io(e: &io::Error) -> Self {
+        // ENXIO, ENODEV, EREMOTEIO
+        #[cfg(unix)]
+        match e.raw_os_error() {
+            Some(6) | Some(121) => return ErrorKind::Nak,
+            Some(19) => return ErrorKind::DeviceGone,
+            _ => (),
+        }
+
+        match e.kind() {
+            io::ErrorKind::PermissionDenied => ErrorKind::PermissionDenied,
+            io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => ErrorKind::Timeout,
+            io::ErrorKind::NotFound => ErrorKind::DeviceGone,
+            io::ErrorKind::Unsupported => ErrorKind::Unsupported,
+            io::ErrorKind::InvalidData => ErrorKind::InvalidResponse,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+impl Error {
+    /// Classify the error.
+    pub fn kind(&self) -> ErrorKind {
+        match *self {
+            Error::UnsupportedOp => ErrorKind::Unsupported,
+            Error::CapabilitiesReadError(ref e) | Error::LowLevelError(ref e) => e.kind(),
+            Error::CapabilitiesParseError(_) => ErrorKind::InvalidResponse,
+            Error::InvalidArgument(_) => ErrorKind::InvalidArgument,
+            Error::InvalidResponse(ref code) => ErrorKind::from_ddc(code),
+            Error::RateLimited(_) => ErrorKind::RateLimited,
+            Error::VerificationFailed { .. } => ErrorKind::VerificationFailed,
+            Error::PermissionDenied { .. } => ErrorKind::PermissionDenied,
+            Error::DisplayError { ref source, .. } => source.kind(),
+        }
+    }
+
+    /// The backend and identifier of the display the error concerns, if
+    /// known.
+    pub fn display_id(&self) -> Option<(Backend, &str)> {
+        match *self {
+            Error::DisplayError { backend, ref id, .. } => Some((backend, id)),
+            _ => None,
+        }
+    }
+
+    /// The DDC/CI protocol error reported by the display, as opposed to a
+    /// failure of the underlying transport.
+    pub fn protocol_error(&self) -> Option<&ddc::ErrorCode> {
+        match *self {
+            Error::CapabilitiesReadError(ref e) | Error::LowLevelError(ref e) => e.protocol_error(),
+            Error::InvalidResponse(ref code) => Some(code),
+            Error::DisplayError { ref source, .. } => source.protocol_error(),
+            _ => None,
+        }
+    }
+
+    /// Whether the display explicitly replied that it does not support the
+    /// requested VCP feature.
+    ///
+    /// Unlike transport failures, this is not worth retrying.
+    pub fn is_unsupported_feature(&self) -> bool {
+        self.kind() == ErrorKind::UnsupportedFeature
+    }
+
+    /// The underlying error, without any display context.
+    pub fn inner(&self) -> &Error {
+        match *self {
+            Error::DisplayError { ref source, .. } => source.inner(),
+            ref e => e,
+        }
+    }
+
+    /// Whether the operation may succeed if retried.
+    pub fn is_transient(&self) -> bool {
+        self.kind().is_transient()
+    }
+
+    /// The backend error that caused the failure, if any.
+    pub fn backend_error(&self) -> Option<&BackendError> {
+        match *self {
+            Error::CapabilitiesReadError(ref e) | Error::LowLevelError(ref e) => Some(e),
+            Error::DisplayError { ref source, .. } => source.backend_error(),
+            _ => None,
+        }
+    }
+
+    /// The OS error that caused the failure, if any.
+    pub fn as_io_error(&self) -> Option<&io::Error> {
+        match *self {
+            Error::CapabilitiesParseError(ref e) => Some(e),
+            Error::DisplayError { ref source, .. } => source.as_io_error(),
+            ref e => e.backend_error().and_then(BackendError::as_io_error),
+        }
+    }
+
+    /// The kind of the OS error that caused the failure, if any.
+    pub fn io_kind(&self) -> Option<io::ErrorKind> {
+        self.as_io_error().map(io::Error::kind)
+    }
+
+    /// The raw OS error code, such as `EBUSY`, that caused the failure, if
+    /// any.
+    pub fn raw_os_error(&self) -> Option<i32> {
+        self.as_io_error().and_then(io::Error::raw_os_error)
+    }
+}
+
+impl From<ddc::ErrorCode> for Error {
+    fn from(code: ddc::ErrorCode) -> Self {
+        Error::InvalidResponse(code)
+    }
+}
+
+impl BackendError {
+    /// The DDC/CI protocol error reported by the display, as opposed to a
+    /// failure of the underlying transport.
+    pub fn protocol_error(&self) -> Option<&ddc::ErrorCode> {
+        match *self {
+            #[cfg(feature = "has-ddc-i2c")]
+            BackendError::I2cDeviceError(ddc_i2c::Error::Ddc(ref code)) => Some(code),
+            #[cfg(feature = "has-ddc-macos")]
+            BackendError::MacOsError(ddc_macos::Error::Ddc(ref code)) => Some(code),
+            #[cfg(all(feature = "has-nvapi", feature = "ddc-i2c"))]
+            BackendError::NvapiError(ddc_i2c::Error::Ddc(ref code)) => Some(code),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// Classify the error.
+    pub fn kind(&self) -> ErrorKind {
+        if let Some(code) = self.protocol_error() {
+            return ErrorKind::from_ddc(code)
+        }
+
+        match *self {
+            #[cfg(feature = "has-ddc-i2c")]
+            BackendError::I2cDeviceError(ddc_i2c::Error::I2c(ref e)) => ErrorKind::from_io(e),
+            #[cfg(feature = "has-ddc-winapi")]
+            BackendError::WinApiError(ref e) => ErrorKind::from_io(e),
+            #[cfg(all(feature = "has-nvapi", feature = "ddc-i2c"))]
+            BackendError::NvapiError(ddc_i2c::Error::I2c(status)) => match status {
+                nvapi::Status::NotSupported | nvapi::Status::NoImplementation => ErrorKind::Unsupported,
+                nvapi::Status::AccessDenied => ErrorKind::PermissionDenied,
+                nvapi::Status::Timeout => ErrorKind::Timeout,
+                nvapi::Status::NvidiaDeviceNotFound | nvapi::Status::HandleInvalidated => ErrorKind::DeviceGone,
+                _ => ErrorKind::Other,
+            },
+            #[cfg(target_os = "linux")]
+            BackendError::BacklightError(ref e) => ErrorKind::from_io(e),
+            #[allow(unreachable_patterns)]
+            _ => ErrorKind::Other,
+        }
+    }
+
+    /// The OS error that caused the failure, for backends that report one.
+    pub fn as_io_error(&self) -> Option<&io::Error> {
+        match *self {
+            #[cfg(feature = "has-ddc-i2c")]
+            BackendError::I2cDeviceError(ddc_i2c::Error::I2c(ref e)) => Some(e),
+            #[cfg(feature = "has-ddc-winapi")]
+            BackendError::WinApiError(ref e) => Some(e),
+            #[cfg(target_os = "linux")]
+            BackendError::BacklightError(ref e) => Some(e),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// The Windows error code, such as `ERROR_GEN_FAILURE`, of a WinAPI
+    /// failure.
+    pub fn as_winapi_code(&self) -> Option<i32> {
+        match *self {
+            #[cfg(feature = "has-ddc-winapi")]
+            BackendError::WinApiError(ref e) => e.raw_os_error(),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// The `kern_return_t` code of a macOS kernel I/O failure.
+    pub fn as_kern_return(&self) -> Option<i32> {
+        match *self {
+            #[cfg(feature = "has-ddc-macos")]
+            BackendError::MacOsError(ddc_macos::Error::Io(code)) => Some(code),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// The status of an NVAPI failure.
+    #[cfg(all(feature = "has-nvapi", feature = "ddc-i2c"))]
+    pub fn as_nvapi_status(&self) -> Option<nvapi::Status> {
+        match *self {
+            BackendError::NvapiError(ddc_i2c::Error::I2c(status)) => Some(status),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
 }
 
 /// Identifying information about an attached display.
@@ -94,9 +522,11 @@ pub struct DisplayInfo {
     pub version: Option<(u8, u8)>,
     /// Serial number of the device
     pub serial: Option<u32>,
-    /// Year the display was manufactured.
+    /// Year the display was manufactured, as the raw EDID offset from 1990.
+    ///
+    /// See `manufacture_date` for the decoded date.
     pub manufacture_year: Option<u8>,
-    /// Week the display was manufactured.
+    /// Week the display was manufactured, as the raw EDID byte.
     pub manufacture_week: Option<u8>,
     /// The model name of the display.
     pub model_name: Option<String>,
@@ -106,10 +536,106 @@ pub struct DisplayInfo {
     pub edid_data: Option<Vec<u8>>,
     /// MCCS VCP version code.
     pub mccs_version: Option<mccs::Version>,
-    /// MCCS VCP feature information.
+    /// The graphics adapter the display is connected to.
+    pub adapter: Option<DisplayAdapter>,
+    /// The CoreGraphics display ID of a macOS display.
+    pub core_graphics_id: Option<u32>,
+    /// The NVAPI display ID of a display on an NVIDIA GPU.
+    pub nvapi_display_id: Option<u32>,
+    /// The Linux DRM connector of the display, such as `card0-HDMI-A-1`.
+    pub drm_connector: Option<String>,
+    /// The parts of the capabilities string that don't describe VCP
+    /// features.
+    pub capabilities: Option<CapabilitiesInfo>,
+    /// Where the display is placed on the desktop.
+    pub bounds: Option<DisplayBounds>,
+    /// Whether the display is built into the computer, such as a laptop
+    /// panel.
+    pub is_internal: Option<bool>,
+    /// MCCS VCP feature information, with the `mccs-db` feature.
+    #[cfg(feature = "mccs-db")]
     pub mccs_database: mccs_db::Database,
 }
 
+/// Identifies the graphics adapter and output that a display is connected to.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DisplayAdapter {
+    /// The locally unique identifier of the adapter, which is stable until
+    /// the system restarts.
+    pub luid: Option<u64>,
+    /// A description of the adapter, usually its name.
+    pub description: Option<String>,
+    /// The index of the adapter output driving the display.
+    pub output: Option<u32>,
+    /// The display source shown on the display, such as `\\.\DISPLAY1`.
+    pub source: Option<String>,
+    /// Whether the source is duplicated onto other displays.
+    ///
+    /// Mirrored displays may be the same physical monitor connected through
+    /// more than one output, so commands sent to each may be applied twice.
+    pub mirrored: bool,
+    /// How far the source is rotated clockwise on the display, in degrees.
+    pub rotation: Option<u16>,
+    /// The kind of connector the display is attached through, such as
+    /// `DisplayPort`.
+    pub connector: Option<String>,
+}
+
+/// The parts of a display's capabilities string besides its model, MCCS
+/// version and VCP features.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CapabilitiesInfo {
+    /// The protocol class.
+    pub protocol: Option<mccs::Protocol>,
+    /// The type of display.
+    pub display_type: Option<mccs::Type>,
+    /// The supported DDC/CI commands.
+    pub commands: Vec<u8>,
+    /// Whether the display passed Microsoft's WHQL testing, usually `1`.
+    pub ms_whql: Option<u8>,
+    /// Video Display Information Format blocks.
+    pub vdif: Vec<mccs::VdifData>,
+    /// Unrecognized entries, such as vendor-specific blocks.
+    pub unknown_tags: Vec<mccs::UnknownTag>,
+}
+
+impl CapabilitiesInfo {
+    /// Collect the parts of parsed capabilities that aren't kept elsewhere.
+    pub fn from_capabilities(caps: &mccs::Capabilities) -> Self {
+        CapabilitiesInfo {
+            protocol: caps.protocol.clone(),
+            display_type: caps.ty.clone(),
+            commands: caps.commands.clone(),
+            ms_whql: caps.ms_whql,
+            vdif: caps.vdif.clone(),
+            unknown_tags: caps.unknown_tags.clone(),
+        }
+    }
+}
+
+/// The area of the desktop covered by a display, in points.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DisplayBounds {
+    /// The left edge, relative to the main display.
+    pub x: i32,
+    /// The top edge, relative to the main display.
+    pub y: i32,
+    /// The width of the display.
+    pub width: u32,
+    /// The height of the display.
+    pub height: u32,
+}
+
+/// Which information wins when merging two `DisplayInfo`s that both know
+/// something, as with `DisplayInfo::merge_with`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MergePolicy {
+    /// Keep existing information, only filling in what is missing.
+    PreferSelf,
+    /// Replace existing information with anything the other info knows.
+    PreferOther,
+}
+
 impl fmt::Display for DisplayInfo {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}:{}", self.backend, self.id)?;
@@ -144,46 +670,51 @@ impl DisplayInfo {
             serial_number: None,
             edid_data: None,
             mccs_version: None,
+            adapter: None,
+            core_graphics_id: None,
+            nvapi_display_id: None,
+            drm_connector: None,
+            capabilities: None,
+            bounds: None,
+            is_internal: None,
+            #[cfg(feature = "mccs-db")]
             mccs_database: Default::default(),
         }
     }
 
     /// Creates a new `DisplayInfo` from unparsed EDID data.
     ///
-    /// May fail to parse the EDID data.
+    /// May fail to parse the EDID data. Without the `edid` feature the data
+    /// is kept but not parsed.
     pub fn from_edid(backend: Backend, id: String, edid_data: Vec<u8>) -> io::Result<Self> {
         trace!("DisplayInfo::from_edid({:?}, {})", backend, id);
 
-        let edid = edid::parse(&edid_data)
-            .to_result()
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        let mut info = Self::new(backend, id);
 
-        let mut model_name = None;
-        let mut serial_number = None;
-
-        for desc in edid.descriptors {
-            match desc {
-                edid::Descriptor::SerialNumber(serial) => serial_number = Some(serial),
-                edid::Descriptor::ProductName(model) => model_name = Some(model),
-                _ => (),
+        #[cfg(feature = "edid")]
+        {
+            let edid = edid::parse(&edid_data)
+                .to_result()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+
+            for desc in edid.descriptors {
+                match desc {
+                    edid::Descriptor::SerialNumber(serial) => info.serial_number = Some(serial),
+                    edid::Descriptor::ProductName(model) => info.model_name = Some(model),
+                    _ => (),
+                }
             }
+
+            info.manufacturer_id = Some(String::from_iter(edid.header.vendor.iter()));
+            info.model_id = Some(edid.header.product);
+            info.serial = Some(edid.header.serial);
+            info.version = Some((edid.header.version, edid.header.revision));
+            info.manufacture_year = Some(edid.header.year);
+            info.manufacture_week = Some(edid.header.week);
         }
 
-        Ok(DisplayInfo {
-            backend,
-            id,
-            edid_data: Some(edid_data),
-            manufacturer_id: Some(String::from_iter(edid.header.vendor.iter())),
-            model_id: Some(edid.header.product),
-            serial: Some(edid.header.serial),
-            version: Some((edid.header.version, edid.header.revision)),
-            manufacture_year: Some(edid.header.year),
-            manufacture_week: Some(edid.header.week),
-            model_name,
-            serial_number,
-            mccs_version: None,
-            mccs_database: Default::default(),
-        })
+        info.edid_data = Some(edid_data);
+        Ok(info)
     }
 
     /// Create a new `DisplayInfo` from parsed capabilities.
@@ -200,9 +731,9 @@ impl DisplayInfo {
             backend,
             id,
             model_name: caps.model.clone(),
-            mccs_version: caps.mccs_version.clone(),
+            mccs_version: caps.mccs_version,
             edid_data: caps.edid.clone(),
-            // TODO: VDIF
+            capabilities: Some(CapabilitiesInfo::from_capabilities(caps)),
             serial_number: None,
             manufacturer_id: None,
             model_id: None,
@@ -210,9 +741,17 @@ impl DisplayInfo {
             version: None,
             manufacture_year: None,
             manufacture_week: None,
+            adapter: None,
+            core_graphics_id: None,
+            nvapi_display_id: None,
+            drm_connector: None,
+            bounds: None,
+            is_internal: None,
+            #[cfg(feature = "mccs-db")]
             mccs_database: Default::default(),
         };
 
+        #[cfg(feature = "mccs-db")]
         if let Some(ver) = res.mccs_version.as_ref() {
             res.mccs_database = mccs_db::Database::from_version(ver);
             res.mccs_database.apply_capabilities(caps);
@@ -220,8 +759,9 @@ impl DisplayInfo {
 
         match edid {
             Ok(Some(edid)) => {
-                // TODO: should this be edid.update_from(&res) instead?
-                res.update_from(&edid);
+                // the capabilities describe the display as it presents itself
+                // over DDC/CI, so they take priority over the EDID
+                res.merge_with(&edid, MergePolicy::PreferSelf);
             },
             Ok(None) => (),
             Err(e) => {
@@ -232,53 +772,86 @@ impl DisplayInfo {
         res
     }
 
-    /// Merge in any missing information from another `DisplayInfo`
-    pub fn update_from(&mut self, info: &DisplayInfo) {
-        if self.manufacturer_id.is_none() {
-            self.manufacturer_id = info.manufacturer_id.clone()
-        }
-
-        if self.model_id.is_none() {
-            self.model_id = info.model_id.clone()
-        }
-
-        if self.version.is_none() {
-            self.version = info.version.clone()
-        }
-
-        if self.serial.is_none() {
-            self.serial = info.serial.clone()
-        }
-
-        if self.manufacture_year.is_none() {
-            self.manufacture_year = info.manufacture_year.clone()
-        }
+    /// Create a `DisplayInfo` from previously captured EDID data and
+    /// capabilities string, without access to the display.
+    ///
+    /// The information is merged the same way as for an enumerated display
+    /// whose capabilities are then read with `Display::update_capabilities`.
+    #[cfg(feature = "mccs-db")]
+    pub fn from_raw(backend: Backend, id: String, edid: Option<&[u8]>, caps: Option<&str>) -> io::Result<Self> {
+        trace!("DisplayInfo::from_raw({:?}, {})", backend, id);
+
+        let mut info = match edid {
+            Some(edid) => Self::from_edid(backend, id, edid.to_vec())?,
+            None => Self::new(backend, id),
+        };
 
-        if self.manufacture_week.is_none() {
-            self.manufacture_week = info.manufacture_week.clone()
+        if let Some(caps) = caps {
+            info.update_from_capabilities(&mccs_caps::parse_capabilities(caps)?);
         }
 
-        if self.model_name.is_none() {
-            self.model_name = info.model_name.clone()
-        }
+        Ok(info)
+    }
 
-        if self.serial_number.is_none() {
-            self.serial_number = info.serial_number.clone()
+    /// Merge in information from parsed capabilities, preferring their
+    /// description of the supported features.
+    #[cfg(feature = "mccs-db")]
+    fn update_from_capabilities(&mut self, caps: &mccs::Capabilities) {
+        let info = Self::from_capabilities(self.backend, self.id.clone(), caps);
+        if info.mccs_version.is_some() {
+            self.mccs_database = Default::default();
         }
+        self.update_from(&info);
+    }
 
-        if self.edid_data.is_none() {
-            self.edid_data = info.edid_data.clone()
-        }
+    /// Merge in any missing information from another `DisplayInfo`
+    pub fn update_from(&mut self, info: &DisplayInfo) {
+        self.merge_with(info, MergePolicy::PreferSelf)
+    }
 
-        if self.mccs_version.is_none() {
-            self.mccs_version = info.mccs_version.clone()
+    /// Merge in information from another `DisplayInfo`, with `policy`
+    /// deciding which wins when both know something.
+    pub fn merge_with(&mut self, info: &DisplayInfo, policy: MergePolicy) {
+        fn merge<T: Clone>(dest: &mut Option<T>, src: &Option<T>, policy: MergePolicy) {
+            let replace = match policy {
+                MergePolicy::PreferSelf => dest.is_none(),
+                MergePolicy::PreferOther => src.is_some(),
+            };
+            if replace {
+                dest.clone_from(src)
+            }
         }
 
-        if self.mccs_database.get(0xdf).is_none() {
-            if info.mccs_version.is_some() {
-                self.mccs_version = info.mccs_version.clone()
+        merge(&mut self.manufacturer_id, &info.manufacturer_id, policy);
+        merge(&mut self.model_id, &info.model_id, policy);
+        merge(&mut self.version, &info.version, policy);
+        merge(&mut self.serial, &info.serial, policy);
+        merge(&mut self.manufacture_year, &info.manufacture_year, policy);
+        merge(&mut self.manufacture_week, &info.manufacture_week, policy);
+        merge(&mut self.model_name, &info.model_name, policy);
+        merge(&mut self.serial_number, &info.serial_number, policy);
+        merge(&mut self.edid_data, &info.edid_data, policy);
+        merge(&mut self.mccs_version, &info.mccs_version, policy);
+        merge(&mut self.adapter, &info.adapter, policy);
+        merge(&mut self.core_graphics_id, &info.core_graphics_id, policy);
+        merge(&mut self.nvapi_display_id, &info.nvapi_display_id, policy);
+        merge(&mut self.drm_connector, &info.drm_connector, policy);
+        merge(&mut self.capabilities, &info.capabilities, policy);
+        merge(&mut self.bounds, &info.bounds, policy);
+        merge(&mut self.is_internal, &info.is_internal, policy);
+
+        #[cfg(feature = "mccs-db")]
+        {
+            let replace = match policy {
+                MergePolicy::PreferSelf => self.mccs_database.get(0xdf).is_none(),
+                MergePolicy::PreferOther => info.mccs_database.get(0xdf).is_some(),
+            };
+            if replace {
+                if info.mccs_version.is_some() {
+                    self.mccs_version = info.mccs_version
+                }
+                self.mccs_database = info.mccs_database.clone()
             }
-            self.mccs_database = info.mccs_database.clone()
         }
     }
 
@@ -295,17 +868,115 @@ impl DisplayInfo {
             let version = mccs::Version::new(version.sh, version.sl);
             if version != mccs::Version::default() {
                 self.mccs_version = Some(version);
-                self.mccs_database = mccs_db::Database::from_version(&version);
+                #[cfg(feature = "mccs-db")]
+                {
+                    self.mccs_database = mccs_db::Database::from_version(&version);
+                }
             }
         }
 
         Ok(())
     }
+
+    /// Iterate over the features described by `mccs_database`.
+    #[cfg(feature = "mccs-db")]
+    pub(crate) fn features(&self) -> impl Iterator<Item = &mccs_db::Descriptor> {
+        (0..=0xff).filter_map(move |code| self.mccs_database.get(code))
+    }
+
+    /// Whether the feature's current value can be read back.
+    ///
+    /// Write-only and momentary features such as degauss (`0x01`) or restore
+    /// factory defaults (`0x04`) are not readable. Features missing from
+    /// `mccs_database` are assumed to be readable.
+    #[cfg_attr(not(feature = "mccs-db"), allow(unused_variables))]
+    pub fn is_readable(&self, code: FeatureCode) -> bool {
+        #[cfg(feature = "mccs-db")]
+        if let Some(desc) = self.mccs_database.get(code) {
+            return desc.access != mccs_db::Access::WriteOnly && !Self::is_momentary_desc(desc)
+        }
+
+        true
+    }
+
+    /// Whether the feature is a momentary action that is triggered by writing
+    /// a non-zero value.
+    #[cfg_attr(not(feature = "mccs-db"), allow(unused_variables))]
+    pub fn is_momentary(&self, code: FeatureCode) -> bool {
+        #[cfg(feature = "mccs-db")]
+        if let Some(desc) = self.mccs_database.get(code) {
+            return Self::is_momentary_desc(desc)
+        }
+
+        false
+    }
+
+    #[cfg(feature = "mccs-db")]
+    fn is_momentary_desc(desc: &mccs_db::Descriptor) -> bool {
+        matches!(desc.ty, mccs_db::ValueType::NonContinuous {
+            interpretation: mccs_db::ValueInterpretation::NonZeroWrite,
+            ..
+        })
+    }
+
+    /// Look up the name of a non-continuous feature value.
+    ///
+    /// For example the input source `0x60` value `0x11` is named "HDMI 1".
+    #[cfg(feature = "mccs-db")]
+    pub fn value_name(&self, code: FeatureCode, value: u16) -> Option<&str> {
+        match self.mccs_database.get(code)?.ty {
+            mccs_db::ValueType::NonContinuous { ref values, .. } => values.get(&(value as u8))?.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Look up a non-continuous feature value by its name.
+    ///
+    /// The comparison ignores ASCII case.
+    #[cfg(feature = "mccs-db")]
+    pub fn value_by_name(&self, code: FeatureCode, name: &str) -> Option<u16> {
+        match self.mccs_database.get(code)?.ty {
+            mccs_db::ValueType::NonContinuous { ref values, .. } => values
+                .iter()
+                .find(|(_, value)| value.as_deref().map(|v| v.eq_ignore_ascii_case(name)).unwrap_or(false))
+                .map(|(&value, _)| value as u16),
+            _ => None,
+        }
+    }
+
+    /// Format a feature value for user display.
+    ///
+    /// Named values are rendered by name, otherwise the value is formatted
+    /// according to the feature's interpretation.
+    #[cfg(feature = "mccs-db")]
+    pub fn format_value(&self, code: FeatureCode, value: &VcpValue) -> String {
+        match self.mccs_database.get(code).map(|desc| &desc.ty) {
+            Some(&mccs_db::ValueType::Continuous { interpretation }) => interpretation.format(value),
+            Some(&mccs_db::ValueType::NonContinuous { interpretation, .. }) =>
+                match self.value_name(code, value.value()) {
+                    Some(name) => format!("{} (0x{:02x})", name, value.value()),
+                    None => interpretation.format(value),
+                },
+            _ => format!("{}", value.value()),
+        }
+    }
+
+    /// Render the features of `mccs_database` as a table.
+    ///
+    /// Any features with an entry in `values` are shown with their current
+    /// value, otherwise the allowed values are listed.
+    #[cfg(feature = "mccs-db")]
+    pub fn feature_table<'a>(&'a self, values: &'a BTreeMap<FeatureCode, VcpValue>) -> FeatureTable<'a> {
+        FeatureTable { info: self, values }
+    }
 }
 
 /// A query to filter out matching displays.
 ///
 /// Most comparisons must match the full string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 pub enum Query {
     /// Matches any display
     Any,
@@ -342,7 +1013,11 @@ impl Query {
 }
 
 /// Identifies the backend driver used to communicate with a display.
+///
+/// Backends may be added in future releases, and those implemented outside
+/// of this crate are represented by `Backend::Other`.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
 pub enum Backend {
     /// Linux i2c-dev driver
     I2cDevice,
@@ -352,22 +1027,23 @@ pub enum Backend {
     Nvapi,
     /// MacOS APIs
     MacOS,
+    /// A backend that isn't built into this crate, identified by its name.
+    Other(&'static str),
 }
 
 impl fmt::Display for Backend {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", match *self {
-            Backend::I2cDevice => "i2c-dev",
-            Backend::WinApi => "winapi",
-            Backend::Nvapi => "nvapi",
-            Backend::MacOS => "macos",
-        })
+        f.pad(self.name())
     }
 }
 
 impl str::FromStr for Backend {
     type Err = ();
 
+    /// Parse the name of a built in backend.
+    ///
+    /// Names of other backends can't be parsed, as they aren't known ahead
+    /// of time.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
             "i2c-dev" => Backend::I2cDevice,
@@ -379,7 +1055,61 @@ impl str::FromStr for Backend {
     }
 }
 
+#[cfg(feature = "serde")]
+impl Serialize for Backend {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Backend {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse()
+            .map_err(|_| serde::de::Error::custom(format!("unknown backend {:?}", name)))
+    }
+}
+
 impl Backend {
+    /// The name of the backend, as used by its `Display` and `FromStr` impls.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Backend::I2cDevice => "i2c-dev",
+            Backend::WinApi => "winapi",
+            Backend::Nvapi => "nvapi",
+            Backend::MacOS => "macos",
+            Backend::Other(name) => name,
+        }
+    }
+
+    /// The cargo feature that builds the backend into this crate.
+    pub fn feature_name(&self) -> Option<&'static str> {
+        match *self {
+            Backend::I2cDevice => Some("ddc-i2c"),
+            Backend::WinApi => Some("ddc-winapi"),
+            Backend::Nvapi => Some("nvapi"),
+            Backend::MacOS => Some("ddc-macos"),
+            Backend::Other(_) => None,
+        }
+    }
+
+    /// What the backend is able to do, regardless of the display attached.
+    ///
+    /// Nothing is known about other backends, so they are assumed to support
+    /// everything.
+    pub fn capabilities(&self) -> BackendCaps {
+        match *self {
+            Backend::WinApi => BackendCaps {
+                read_edid: false,
+                table: false,
+                commands: false,
+                ..BackendCaps::ALL
+            },
+            _ => BackendCaps::ALL,
+        }
+    }
+
     /// Enumerate the possible backends.
     ///
     /// Backends not supported for the current platform will be excluded.
@@ -397,158 +1127,641 @@ impl Backend {
     }
 }
 
+/// The backends built into this crate for the current platform, along with
+/// the cargo features that enable them.
+///
+/// Backends that are missing here can't find any displays, such as NVAPI
+/// without the `nvapi` feature.
+pub fn built_backends() -> Vec<(Backend, &'static str)> {
+    Backend::values()
+        .iter()
+        .filter_map(|&backend| backend.feature_name().map(|feature| (backend, feature)))
+        .collect()
+}
+
+/// The operations a backend supports, as returned by `Backend::capabilities`.
+///
+/// Support may still depend on the display itself, so a supported operation
+/// can fail at runtime.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BackendCaps {
+    /// Reading the EDID over the display's DDC channel.
+    pub read_edid: bool,
+    /// Reading the capabilities string.
+    pub capabilities: bool,
+    /// Reading the timing report.
+    pub timing_report: bool,
+    /// Reading and writing table features.
+    pub table: bool,
+    /// Executing individual DDC/CI commands.
+    pub commands: bool,
+    /// Changing the display's power mode (`0xd6`).
+    pub power_control: bool,
+}
+
+impl BackendCaps {
+    const ALL: Self = BackendCaps {
+        read_edid: true,
+        capabilities: true,
+        timing_report: true,
+        table: true,
+        commands: true,
+        power_control: true,
+    };
+
+    /// Whether the backend is able to perform an operation.
+    pub fn supports(&self, op: Operation) -> bool {
+        match op {
+            Operation::ReadEdid => self.read_edid,
+            Operation::Capabilities => self.capabilities,
+            Operation::TimingReport => self.timing_report,
+            Operation::Table => self.table,
+            Operation::Command => self.commands,
+            Operation::GetVcpFeature | Operation::SetVcpFeature | Operation::SaveCurrentSettings => true,
+        }
+    }
+}
+
 /// An active handle to a connected display.
-pub struct Display {
+///
+/// The handle defaults to one of the backends built into this crate, but any
+/// transport implementing `Ddc` can be used to reuse the capabilities and
+/// MCCS database handling of a `Display`.
+pub struct Display<H = Handle> {
     /// The inner communication handle used for DDC commands.
-    pub handle: Handle,
+    pub handle: H,
     /// Information about the connected display.
     pub info: DisplayInfo,
+    #[cfg(feature = "mccs-db")]
     filled_caps: bool,
+    #[cfg(feature = "mccs-db")]
+    partial_caps: Option<caps::PartialCapabilities>,
+    save_interval: Duration,
+    last_save: Option<Instant>,
+    retry: RetryPolicy,
+    metrics: Option<Metrics>,
+    dry_run: bool,
+    delays: Delays,
+    cache: ValueCache,
+    settle_times: BTreeMap<FeatureCode, Duration>,
+    blacklist: Blacklist,
+    bus: bus::Bus,
 }
 
 impl Display {
-    /// Create a new display from the specified handle.
-    pub fn new(handle: Handle, info: DisplayInfo) -> Self {
-        Display {
-            handle,
-            info,
-            filled_caps: false,
-        }
-    }
-
     /// Enumerate all detected displays.
+    ///
+    /// End users can work around problematic backends and displays with
+    /// environment variables: `DDC_HI_BACKENDS` lists the backends to
+    /// enumerate by name, such as `i2c-dev,nvapi`, `DDC_HI_DELAY_PERCENT`
+    /// scales the DDC/CI delays (see `Delays::scaled`), and `DDC_HI_ATTEMPTS`
+    /// sets the number of attempts made at each operation.
     pub fn enumerate() -> Vec<Self> {
-        let mut displays = Vec::new();
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!("enumerate", displays = tracing::field::Empty).entered();
+
+        let displays: Vec<_> = Self::enumerate_iter().collect();
 
         #[cfg(feature = "has-ddc-i2c")]
+        if env::backend_enabled(Backend::I2cDevice)
+            && !displays
+                .iter()
+                .any(|display| display.info.backend == Backend::I2cDevice)
         {
-            use std::os::unix::fs::MetadataExt;
-
-            if let Ok(devs) = ddc_i2c::I2cDeviceEnumerator::new() {
-                displays.extend(
-                    devs.map(|mut ddc| -> Result<_, String> {
-                        let id = ddc
-                            .inner_ref()
-                            .inner_ref()
-                            .metadata()
-                            .map(|meta| meta.rdev())
-                            .unwrap_or(Default::default());
-                        let mut edid = vec![0u8; 0x100];
-                        ddc.read_edid(0, &mut edid)
-                            .map_err(|e| format!("failed to read EDID for i2c-{}: {}", id, e))?;
-                        let info = DisplayInfo::from_edid(Backend::I2cDevice, id.to_string(), edid)
-                            .map_err(|e| format!("failed to parse EDID for i2c-{}: {}", id, e))?;
-                        Ok(Display::new(Handle::I2cDevice(ddc), info))
-                    })
-                    .filter_map(|d| match d {
-                        Ok(v) => Some(v),
-                        Err(e) => {
-                            warn!("Failed to enumerate a display: {}", e);
-                            None
-                        },
-                    }),
-                )
-            }
+            permission::warn_inaccessible_i2c();
         }
 
+        metrics::record_detected(displays.len());
+        #[cfg(feature = "tracing")]
+        span.record("displays", displays.len());
+
+        displays
+    }
+
+    /// Lazily enumerate the detected displays.
+    ///
+    /// Each display is only opened and identified as the iterator reaches
+    /// it, so callers looking for a particular display can stop early. The
+    /// same environment variables as `Display::enumerate` are honoured.
+    pub fn enumerate_iter() -> impl Iterator<Item = Self> {
+        warning::reset();
+
+        let displays = iter::empty();
+
+        #[cfg(feature = "has-ddc-i2c")]
+        let displays = displays.chain(
+            env::backend_enabled(Backend::I2cDevice)
+                .then(Self::enumerate_i2c)
+                .into_iter()
+                .flatten(),
+        );
+
         #[cfg(feature = "has-ddc-winapi")]
-        {
-            if let Ok(devs) = ddc_winapi::Monitor::enumerate() {
-                displays.extend(devs.into_iter().map(|ddc| {
-                    let info = DisplayInfo::new(Backend::WinApi, ddc.description());
-                    Display::new(Handle::WinApi(ddc), info)
-                }))
-            }
-        }
+        let displays = displays.chain(
+            env::backend_enabled(Backend::WinApi)
+                .then(Self::enumerate_winapi)
+                .into_iter()
+                .flatten(),
+        );
 
         #[cfg(feature = "has-ddc-macos")]
-        {
-            if let Ok(devs) = ddc_macos::Monitor::enumerate() {
-                displays.extend(devs.into_iter().map(|ddc| {
-                    let info = ddc
-                        .edid()
-                        .and_then(|edid| DisplayInfo::from_edid(Backend::MacOS, ddc.description(), edid).ok())
-                        .unwrap_or(DisplayInfo::new(Backend::MacOS, ddc.description()));
-                    Display::new(Handle::MacOS(ddc), info)
-                }))
-            }
-        }
+        let displays = displays.chain(
+            env::backend_enabled(Backend::MacOS)
+                .then(Self::enumerate_macos)
+                .into_iter()
+                .flatten(),
+        );
 
         #[cfg(feature = "has-nvapi")]
+        let displays = displays.chain(
+            env::backend_enabled(Backend::Nvapi)
+                .then(Self::enumerate_nvapi)
+                .into_iter()
+                .flatten(),
+        );
+
+        displays.map(env::apply)
+    }
+
+    #[cfg(feature = "has-ddc-i2c")]
+    fn enumerate_i2c() -> impl Iterator<Item = Self> {
+        use std::os::unix::fs::MetadataExt;
+
+        iter::once_with(|| {
+            let connectors = drm::connectors();
+            drm::devices(&connectors).map(move |mut ddc| -> Result<_, String> {
+                let id = ddc
+                    .inner_ref()
+                    .inner_ref()
+                    .metadata()
+                    .map(|meta| meta.rdev())
+                    .unwrap_or(Default::default());
+                let info = drm::display_info(&mut ddc, id, connectors.get(&drm::i2c_bus(id)))?;
+                Ok(Display::from_handle(Handle::I2cDevice(ddc), info))
+            })
+        })
+        .flatten()
+        .filter_map(|d| match d {
+            Ok(v) => Some(v),
+            Err(e) => {
+                warning::failure(Backend::I2cDevice, format!("Failed to enumerate a display: {}", e));
+                None
+            },
+        })
+    }
+
+    // NOTE: WinApi displays can't read their EDID, so it comes from the
+    // registry copy of the display configuration target they are matched to.
+    #[cfg(feature = "has-ddc-winapi")]
+    fn enumerate_winapi() -> impl Iterator<Item = Self> {
+        iter::once_with(|| {
+            let names = display_config::target_names();
+            ddc_winapi::enumerate_monitors()
+                .map_err(|e| warning::failure(Backend::WinApi, format!("WinAPI enumeration failed: {}", e)))
+                .unwrap_or_default()
+                .into_iter()
+                .map(move |monitor| {
+                    let targets = display_config::monitor_device(monitor)
+                        .and_then(|device| names.get(&device).cloned())
+                        .unwrap_or_default();
+                    (monitor, targets)
+                })
+        })
+        .flatten()
+        .flat_map(|(monitor, targets)| {
+            let physical = ddc_winapi::get_physical_monitors_from_hmonitor(monitor).unwrap_or_default();
+            // physical monitors can only be told apart by order
+            let targets = match targets.len() == physical.len() {
+                true => targets,
+                false => Vec::new(),
+            };
+            physical.into_iter().enumerate().map(move |(i, monitor)| {
+                let ddc = unsafe { ddc_winapi::Monitor::new(monitor) };
+                let mut info = DisplayInfo::new(Backend::WinApi, ddc.description());
+                if let Some(target) = targets.get(i) {
+                    target.fill(&mut info);
+                }
+                Display::from_handle(Handle::WinApi(ddc), info)
+            })
+        })
+    }
+
+    #[cfg(feature = "has-ddc-macos")]
+    fn enumerate_macos() -> impl Iterator<Item = Self> {
+        iter::once_with(|| {
+            ddc_macos::Monitor::enumerate()
+                .map_err(|e| warning::failure(Backend::MacOS, format!("macOS enumeration failed: {}", e)))
+                .unwrap_or_default()
+        })
+        .flatten()
+        .map(macos::display)
+    }
+
+    #[cfg(feature = "has-nvapi")]
+    fn enumerate_nvapi() -> impl Iterator<Item = Self> {
+        use std::rc::Rc;
+
+        iter::once_with(|| {
+            nvapi::initialize()
+                .and_then(|_| nvapi::PhysicalGpu::enumerate())
+                .map_err(|e| warning::failure(Backend::Nvapi, format!("NVAPI enumeration failed: {}", e)))
+                .ok()
+        })
+        .flatten()
+        .flatten()
+        .flat_map(|gpu| {
+            let gpu = Rc::new(gpu);
+            let id_prefix = gpu.short_name().unwrap_or("NVAPI".into());
+            let adapter = DisplayAdapter {
+                description: gpu.full_name().ok(),
+                ..Default::default()
+            };
+            let ids = gpu
+                .display_ids_connected(nvapi::ConnectedIdsFlags::empty())
+                .unwrap_or_default();
+            ids.into_iter().filter_map(move |id| {
+                // TODO: it says mask, is it actually `1<<display_id` instead?
+                let mut i2c = nvapi::I2c::new(gpu.clone(), id.display_id);
+                // docs seem to indicate the port is not optional, but the one example I can find
+                // keeps it unset, so only try others if the display doesn't reply
+                i2c.set_port(None, true);
+
+                let mut ddc = ddc_i2c::I2cDdc::new(i2c);
+
+                let idstr = format!("{}/{}:{:?}", id_prefix, id.display_id, id.connector);
+                nvidia::select_port(&mut ddc, &idstr);
+                let res = nvidia::read_edid(&gpu, id.display_id, &mut ddc, &idstr)
+                    .map_err(|e| format!("failed to read EDID: {}", e))
+                    .and_then(|edid| {
+                        DisplayInfo::from_edid(Backend::Nvapi, idstr, edid)
+                            .map_err(|e| format!("failed to parse EDID: {}", e))
+                    })
+                    .map(|mut info| {
+                        info.adapter = Some(DisplayAdapter {
+                            connector: Some(id.connector.to_string()),
+                            ..adapter.clone()
+                        });
+                        info.nvapi_display_id = Some(id.display_id);
+                        Display::from_handle(Handle::Nvapi(ddc), info)
+                    });
+                match res {
+                    Ok(ddc) => Some(ddc),
+                    Err(e) => {
+                        warning::failure(
+                            Backend::Nvapi,
+                            format!(
+                                "Failed to enumerate NVAPI display {}/{}:{:?}: {}",
+                                id_prefix, id.display_id, id.connector, e
+                            ),
+                        );
+                        None
+                    },
+                }
+            })
+        })
+    }
+
+    /// Open a second, independent handle to the same display.
+    ///
+    /// The new display shares this one's information and settings, but not
+    /// its cached values, so each worker can have a handle of its own
+    /// without enumerating again. See `Handle::try_clone` for the backends
+    /// that support this.
+    pub fn try_clone(&self) -> Result<Self, Error> {
+        trace!("Display::try_clone");
+
+        let handle = self.handle.try_clone().map_err(|e| self.context(e))?;
+        let mut display = Display::new(handle, self.info.clone());
+        #[cfg(feature = "mccs-db")]
         {
-            use std::rc::Rc;
-
-            if let Ok(_) = nvapi::initialize() {
-                if let Ok(gpus) = nvapi::PhysicalGpu::enumerate() {
-                    for gpu in gpus {
-                        let gpu = Rc::new(gpu);
-                        let id_prefix = gpu.short_name().unwrap_or("NVAPI".into());
-                        if let Ok(ids) = gpu.display_ids_connected(nvapi::ConnectedIdsFlags::empty()) {
-                            for id in ids {
-                                // TODO: it says mask, is it actually `1<<display_id` instead?
-                                let mut i2c = nvapi::I2c::new(gpu.clone(), id.display_id);
-                                // TODO: port=Some(1) instead? docs seem to indicate it's not optional, but the one
-                                // example I can find keeps it unset so...
-                                i2c.set_port(None, true);
-
-                                // hack around broken nvidia drivers
-                                // the register argument doesn't seem to work at all
-                                // so write the edid eeprom offset here first
-                                i2c.set_address(0x50);
-                                let _ = i2c.nvapi_write(&[], &[0]);
-
-                                let mut ddc = ddc_i2c::I2cDdc::new(i2c);
-
-                                let idstr = format!("{}/{}:{:?}", id_prefix, id.display_id, id.connector);
-                                let mut edid = vec![0u8; 0x80]; // 0x100
-                                let res = ddc
-                                    .read_edid(0, &mut edid)
-                                    .map_err(|e| format!("failed to read EDID: {}", e))
-                                    .and_then(|_| {
-                                        DisplayInfo::from_edid(Backend::Nvapi, idstr, edid)
-                                            .map_err(|e| format!("failed to parse EDID: {}", e))
-                                    })
-                                    .map(|info| Display::new(Handle::Nvapi(ddc), info));
-                                match res {
-                                    Ok(ddc) => displays.push(ddc),
-                                    Err(e) => warn!(
-                                        "Failed to enumerate NVAPI display {}/{}:{:?}: {}",
-                                        id_prefix, id.display_id, id.connector, e
-                                    ),
-                                }
-                            }
-                        }
-                    }
+            display.filled_caps = self.filled_caps;
+        }
+        display.save_interval = self.save_interval;
+        display.retry = self.retry.clone();
+        display.metrics = self.metrics.as_ref().map(|_| Default::default());
+        display.dry_run = self.dry_run;
+        display.delays = self.delays;
+        display.settle_times = self.settle_times.clone();
+        display.blacklist = self.blacklist.clone();
+        display.bus = self.bus.clone();
+
+        Ok(display)
+    }
+
+    /// Read the current values of several VCP features at once.
+    ///
+    /// The bus is waited on once for the whole batch, and the requests are
+    /// then issued back to back, so only the remainder of the inter-command
+    /// delay is waited out between them instead of a full sleep per call. A
+    /// failure to read one feature does not prevent the rest from being read;
+    /// results are returned in the same order as `codes`.
+    pub fn get_vcp_features(&mut self, codes: &[FeatureCode]) -> Vec<Result<VcpValue, Error>> {
+        trace!("Display::get_vcp_features({:?})", codes);
+
+        let delays = self.delays;
+        let op = Operation::GetVcpFeature;
+        bus::wait(&self.bus);
+
+        let mut failed = false;
+        let values = codes
+            .iter()
+            .map(|&code| {
+                self.blacklist.check(op, Some(code)).map_err(|e| self.context(e))?;
+                if failed {
+                    bus::busy(&self.bus, delays.operation_delay(op, true));
+                    bus::wait(&self.bus);
                 }
-            }
+                let res = self.attempt(op, Some(code), |handle| match Commands::new(handle, delays) {
+                    Some(mut ddc) => ddc.get_vcp_feature(code),
+                    None => handle.get_vcp_feature(code),
+                });
+                failed = res.is_err();
+                res
+            })
+            .collect();
+
+        bus::busy(&self.bus, delays.operation_delay(op, failed));
+        values
+    }
+
+    /// Whether the display's backend is able to perform an operation.
+    pub fn supports(&self, op: Operation) -> bool {
+        self.handle.supports(op)
+    }
+
+    /// Trigger a momentary action, such as degauss (`0x01`).
+    ///
+    /// Fails with `Error::InvalidArgument` if the display's MCCS database
+    /// describes the feature as something other than a momentary action.
+    pub fn trigger(&mut self, code: FeatureCode) -> Result<(), Error> {
+        trace!("Display::trigger(0x{:02x})", code);
+
+        #[cfg(feature = "mccs-db")]
+        if self.info.mccs_database.get(code).is_some() && !self.info.is_momentary(code) {
+            return Err(Error::InvalidArgument(format!(
+                "feature 0x{:02x} is not a momentary action",
+                code
+            )))
         }
 
-        displays
+        self.set_vcp_feature(code, 1)
     }
 
-    /// Updates the display info with data retrieved from the device's
-    /// reported capabilities.
-    pub fn update_capabilities(&mut self) -> Result<(), Error> {
-        if !self.filled_caps {
-            let (backend, id) = (self.info.backend, self.info.id.clone());
-            let caps = self.handle.capabilities()?;
-            let info = DisplayInfo::from_capabilities(backend, id, &caps);
-            if info.mccs_version.is_some() {
-                self.info.mccs_database = Default::default();
-            }
-            self.info.update_from(&info);
+    /// Instruct the display to store its current settings.
+    ///
+    /// If `verify` is set, this waits out the delay the MCCS specification
+    /// requires after the command and then checks that the display responds.
+    /// Because saving writes to the display's EEPROM, calls made within
+    /// `save_interval` of a previous save are refused with
+    /// `Error::RateLimited`.
+    pub fn save_settings(&mut self, verify: bool) -> Result<(), Error> {
+        trace!("Display::save_settings({})", verify);
+
+        if let Some(remaining) = self
+            .last_save
+            .and_then(|last| self.save_interval.checked_sub(last.elapsed()))
+        {
+            return Err(self.context(Error::RateLimited(remaining)))
+        }
+
+        self.save_current_settings()?;
+        self.last_save = Some(Instant::now());
+
+        if verify {
+            self.get_vcp_feature(0xdf)?;
         }
 
         Ok(())
     }
 
+    /// The minimum time allowed between calls to `save_settings`.
+    pub fn save_interval(&self) -> Duration {
+        self.save_interval
+    }
+
+    /// Change the minimum time allowed between calls to `save_settings`.
+    pub fn set_save_interval(&mut self, interval: Duration) {
+        self.save_interval = interval;
+    }
+
+    /// Whether writes are skipped rather than sent to the display.
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Enable or disable dry-run mode.
+    ///
+    /// In dry-run mode, VCP feature writes, table writes and saving settings
+    /// are logged and reported as successful without being sent to the
+    /// display. Reads are unaffected.
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+}
+
+impl<H> Display<H> {
+    /// Create a new display from the specified handle.
+    pub fn new(handle: H, info: DisplayInfo) -> Self {
+        Display {
+            handle,
+            #[cfg(feature = "mccs-db")]
+            filled_caps: false,
+            #[cfg(feature = "mccs-db")]
+            partial_caps: None,
+            save_interval: Duration::from_secs(5),
+            last_save: None,
+            retry: RetryPolicy::new(),
+            metrics: None,
+            dry_run: false,
+            delays: Delays::SPEC,
+            cache: Default::default(),
+            settle_times: settle::default_settle_times(),
+            blacklist: Default::default(),
+            bus: bus::Bus::from_info(&info),
+            info,
+        }
+    }
+
+    /// Take the handle out of the display, giving up its settings and
+    /// state.
+    pub fn into_handle(self) -> H {
+        self.handle
+    }
+
+    #[cfg(feature = "mccs-db")]
+    fn fill_capabilities(&mut self, caps: &mccs::Capabilities) {
+        self.info.update_from_capabilities(caps);
+    }
+
+    /// Attach the identity of this display to an error.
+    pub(crate) fn context(&self, error: Error) -> Error {
+        match error {
+            e @ Error::DisplayError { .. } => e,
+            e => Error::DisplayError {
+                backend: self.info.backend,
+                id: self.info.id.clone(),
+                source: Box::new(e.with_permission_hint(self.info.backend, &self.info.device_path())),
+            },
+        }
+    }
+}
+
+impl<H: Ddc<Error = Error>> Display<H> {
     /// Update some display info.
     pub fn update_from_ddc(&mut self) -> Result<(), Error> {
-        self.info.update_from_ddc(&mut self.handle)
+        self.info.update_from_ddc(&mut self.handle).map_err(|e| self.context(e))
     }
 }
 
+impl DdcHost for Display {
+    type Error = Error;
+
+    fn sleep(&mut self) {
+        bus::wait(&self.bus);
+        self.handle.sleep()
+    }
+}
+
+impl Ddc for Display {
+    fn capabilities_string(&mut self) -> Result<Vec<u8>, Self::Error> {
+        let delays = self.delays;
+        self.run(Operation::Capabilities, None, |handle| {
+            match Commands::new(handle, delays) {
+                Some(mut ddc) => ddc.capabilities_string(),
+                None => handle.capabilities_string(),
+            }
+        })
+    }
+
+    fn get_vcp_feature(&mut self, code: FeatureCode) -> Result<VcpValue, Self::Error> {
+        let delays = self.delays;
+        self.run(Operation::GetVcpFeature, Some(code), |handle| {
+            match Commands::new(handle, delays) {
+                Some(mut ddc) => ddc.get_vcp_feature(code),
+                None => handle.get_vcp_feature(code),
+            }
+        })
+    }
+
+    fn set_vcp_feature(&mut self, code: FeatureCode, value: u16) -> Result<(), Self::Error> {
+        if self.dry_run {
+            info!(
+                "Dry run: not setting feature 0x{:02x} of {} to {}",
+                code, self.info, value
+            );
+            return Ok(())
+        }
+
+        // momentary actions such as factory resets may change any feature
+        match self.info.is_momentary(code) {
+            true => self.cache.clear(),
+            false => self.cache.invalidate(code),
+        }
+
+        let delays = self.delays;
+        self.run(Operation::SetVcpFeature, Some(code), |handle| {
+            match Commands::new(handle, delays) {
+                Some(mut ddc) => ddc.set_vcp_feature(code, value),
+                None => handle.set_vcp_feature(code, value),
+            }
+        })?;
+
+        Ok(())
+    }
+
+    fn save_current_settings(&mut self) -> Result<(), Self::Error> {
+        if self.dry_run {
+            info!("Dry run: not saving the settings of {}", self.info);
+            return Ok(())
+        }
+
+        let delays = self.delays;
+        self.run(Operation::SaveCurrentSettings, None, |handle| {
+            match Commands::new(handle, delays) {
+                Some(mut ddc) => ddc.save_current_settings(),
+                None => handle.save_current_settings(),
+            }
+        })
+    }
+
+    fn get_timing_report(&mut self) -> Result<TimingMessage, Self::Error> {
+        let delays = self.delays;
+        self.run(Operation::TimingReport, None, |handle| {
+            match Commands::new(handle, delays) {
+                Some(mut ddc) => ddc.get_timing_report(),
+                None => handle.get_timing_report(),
+            }
+        })
+    }
+}
+
+impl Edid for Display {
+    type EdidError = Error;
+
+    fn read_edid(&mut self, offset: u8, data: &mut [u8]) -> Result<usize, Self::EdidError> {
+        self.run(Operation::ReadEdid, None, |handle| handle.read_edid(offset, data))
+    }
+}
+
+impl DdcTable for Display {
+    fn table_read(&mut self, code: FeatureCode) -> Result<Vec<u8>, Self::Error> {
+        let delays = self.delays;
+        self.run(Operation::Table, Some(code), |handle| {
+            match Commands::new(handle, delays) {
+                Some(mut ddc) => ddc.table_read(code),
+                None => handle.table_read(code),
+            }
+        })
+    }
+
+    fn table_write(&mut self, code: FeatureCode, offset: u16, value: &[u8]) -> Result<(), Self::Error> {
+        if self.dry_run {
+            info!(
+                "Dry run: not writing {} bytes at offset {} of table 0x{:02x} of {}",
+                value.len(),
+                offset,
+                code,
+                self.info
+            );
+            return Ok(())
+        }
+
+        let delays = self.delays;
+        self.run(Operation::Table, Some(code), |handle| {
+            match Commands::new(handle, delays) {
+                Some(mut ddc) => ddc.table_write(code, offset, value),
+                None => handle.table_write(code, offset, value),
+            }
+        })
+    }
+}
+
+/// An operation that a `Handle` may or may not support.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Operation {
+    /// Reading the EDID over the display's DDC channel.
+    ReadEdid,
+    /// Reading the capabilities string.
+    Capabilities,
+    /// Reading VCP feature values.
+    GetVcpFeature,
+    /// Writing VCP feature values.
+    SetVcpFeature,
+    /// Saving the current settings.
+    SaveCurrentSettings,
+    /// Reading the timing report.
+    TimingReport,
+    /// Reading and writing table features.
+    Table,
+    /// Executing individual DDC/CI commands, as used for chunked table
+    /// transfers and raw commands.
+    Command,
+}
+
 /// A handle allowing communication with a display
+#[non_exhaustive]
 pub enum Handle {
     #[doc(hidden)]
     #[cfg(feature = "has-ddc-i2c")]
@@ -562,13 +1775,186 @@ pub enum Handle {
     #[doc(hidden)]
     #[cfg(feature = "has-nvapi")]
     Nvapi(ddc_i2c::I2cDdc<nvapi::I2c<::std::rc::Rc<nvapi::PhysicalGpu>>>),
+    #[doc(hidden)]
+    #[cfg(feature = "testing")]
+    Snapshot(testing::Snapshot),
 }
 
 impl Handle {
     /// Request and parse the display's capabilities string.
+    #[cfg(feature = "mccs-db")]
     pub fn capabilities(&mut self) -> Result<mccs::Capabilities, Error> {
         mccs_caps::parse_capabilities(&self.capabilities_string()?).map_err(Error::CapabilitiesParseError)
     }
+
+    /// The underlying i2c-dev device, for operations that aren't wrapped.
+    #[cfg(feature = "has-ddc-i2c")]
+    pub fn as_i2c(&self) -> Option<&ddc_i2c::I2cDeviceDdc> {
+        match *self {
+            Handle::I2cDevice(ref inner) => Some(inner),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// The underlying i2c-dev device, for operations that aren't wrapped.
+    #[cfg(feature = "has-ddc-i2c")]
+    pub fn as_i2c_mut(&mut self) -> Option<&mut ddc_i2c::I2cDeviceDdc> {
+        match *self {
+            Handle::I2cDevice(ref mut inner) => Some(inner),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// The underlying Windows monitor, for operations that aren't wrapped.
+    #[cfg(feature = "has-ddc-winapi")]
+    pub fn as_winapi(&self) -> Option<&ddc_winapi::Monitor> {
+        match *self {
+            Handle::WinApi(ref inner) => Some(inner),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// The underlying Windows monitor, for operations that aren't wrapped.
+    #[cfg(feature = "has-ddc-winapi")]
+    pub fn as_winapi_mut(&mut self) -> Option<&mut ddc_winapi::Monitor> {
+        match *self {
+            Handle::WinApi(ref mut inner) => Some(inner),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// The underlying macOS monitor, for operations that aren't wrapped.
+    #[cfg(feature = "has-ddc-macos")]
+    pub fn as_macos(&self) -> Option<&ddc_macos::Monitor> {
+        match *self {
+            Handle::MacOS(ref inner) => Some(inner),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// The underlying macOS monitor, for operations that aren't wrapped.
+    #[cfg(feature = "has-ddc-macos")]
+    pub fn as_macos_mut(&mut self) -> Option<&mut ddc_macos::Monitor> {
+        match *self {
+            Handle::MacOS(ref mut inner) => Some(inner),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// The underlying NVAPI I2C bus, for operations that aren't wrapped.
+    #[cfg(feature = "has-nvapi")]
+    pub fn as_nvapi(&self) -> Option<&ddc_i2c::I2cDdc<nvapi::I2c<::std::rc::Rc<nvapi::PhysicalGpu>>>> {
+        match *self {
+            Handle::Nvapi(ref inner) => Some(inner),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// The underlying NVAPI I2C bus, for operations that aren't wrapped.
+    #[cfg(feature = "has-nvapi")]
+    pub fn as_nvapi_mut(&mut self) -> Option<&mut ddc_i2c::I2cDdc<nvapi::I2c<::std::rc::Rc<nvapi::PhysicalGpu>>>> {
+        match *self {
+            Handle::Nvapi(ref mut inner) => Some(inner),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// Open a second, independent handle to the same display.
+    ///
+    /// Only i2c-dev and macOS displays can be reopened. The other backends
+    /// fail with `Error::UnsupportedOp`.
+    pub fn try_clone(&self) -> Result<Self, Error> {
+        match *self {
+            #[cfg(feature = "has-ddc-i2c")]
+            Handle::I2cDevice(ref i2c) => {
+                use std::os::unix::fs::MetadataExt;
+
+                let rdev = i2c
+                    .inner_ref()
+                    .inner_ref()
+                    .metadata()
+                    .map_err(|e| BackendError::I2cDeviceError(ddc_i2c::Error::I2c(e)))?
+                    .rdev();
+                ddc_i2c::from_i2c_device(format!("/dev/i2c-{}", drm::i2c_bus(rdev)))
+                    .map(Handle::I2cDevice)
+                    .map_err(|e| BackendError::I2cDeviceError(ddc_i2c::Error::I2c(e)).into())
+            },
+            #[cfg(feature = "has-ddc-macos")]
+            Handle::MacOS(ref monitor) => {
+                let id = monitor.handle().id;
+                ddc_macos::Monitor::enumerate()
+                    .map_err(BackendError::MacOsError)?
+                    .into_iter()
+                    .find(|monitor| monitor.handle().id == id)
+                    .map(Handle::MacOS)
+                    .ok_or_else(|| Error::InvalidArgument(format!("macOS display {} is gone", id)))
+            },
+            // physical monitors can't be reacquired without their HMONITOR
+            #[cfg(feature = "has-ddc-winapi")]
+            Handle::WinApi(_) => Err(Error::UnsupportedOp),
+            // the bus doesn't expose its GPU or display
+            #[cfg(feature = "has-nvapi")]
+            Handle::Nvapi(_) => Err(Error::UnsupportedOp),
+            #[cfg(feature = "testing")]
+            Handle::Snapshot(_) => Err(Error::UnsupportedOp),
+        }
+    }
+
+    /// Whether the backend is able to perform an operation.
+    ///
+    /// Support may still depend on the display itself, so a supported
+    /// operation can fail at runtime.
+    #[allow(unused_variables)]
+    pub fn supports(&self, op: Operation) -> bool {
+        match *self {
+            #[cfg(feature = "has-ddc-i2c")]
+            Handle::I2cDevice(_) => Backend::I2cDevice.capabilities().supports(op),
+            #[cfg(feature = "has-ddc-winapi")]
+            Handle::WinApi(_) => Backend::WinApi.capabilities().supports(op),
+            #[cfg(feature = "has-ddc-macos")]
+            Handle::MacOS(_) => Backend::MacOS.capabilities().supports(op),
+            #[cfg(feature = "has-nvapi")]
+            Handle::Nvapi(_) => Backend::Nvapi.capabilities().supports(op),
+            #[cfg(feature = "testing")]
+            Handle::Snapshot(_) => op != Operation::Command,
+        }
+    }
+
+    /// Execute a single DDC/CI command, for backends that allow it.
+    pub(crate) fn execute_with<C: Command>(&mut self, command: C, delays: &Delays) -> Result<C::Ok, Error> {
+        #[cfg(feature = "ddc-dump")]
+        return dump::execute(self, command, delays);
+
+        #[cfg(not(feature = "ddc-dump"))]
+        self.execute_backend(command, delays)
+    }
+
+    #[allow(unused_variables)]
+    pub(crate) fn execute_backend<C: Command>(&mut self, command: C, delays: &Delays) -> Result<C::Ok, Error> {
+        match *self {
+            #[cfg(feature = "has-ddc-i2c")]
+            Handle::I2cDevice(ref mut i2c) =>
+                delays::execute(i2c, command, delays).map_err(|e| BackendError::I2cDeviceError(e).into()),
+            #[cfg(feature = "has-ddc-winapi")]
+            Handle::WinApi(_) => Err(Error::UnsupportedOp),
+            #[cfg(feature = "has-ddc-macos")]
+            Handle::MacOS(ref mut monitor) =>
+                ddc::DdcCommand::execute(monitor, command).map_err(|e| BackendError::MacOsError(e).into()),
+            #[cfg(feature = "has-nvapi")]
+            Handle::Nvapi(ref mut i2c) =>
+                delays::execute(i2c, command, delays).map_err(|e| BackendError::NvapiError(e).into()),
+            #[cfg(feature = "testing")]
+            Handle::Snapshot(_) => Err(Error::UnsupportedOp),
+        }
+    }
 }
 
 impl ddc::DdcHost for Handle {
@@ -584,12 +1970,18 @@ impl ddc::DdcHost for Handle {
             Handle::MacOS(ref mut monitor) => monitor.sleep(),
             #[cfg(feature = "has-nvapi")]
             Handle::Nvapi(ref mut i2c) => i2c.sleep(),
+            #[cfg(feature = "testing")]
+            Handle::Snapshot(_) => (),
         }
     }
 }
 
 impl Ddc for Handle {
     fn capabilities_string(&mut self) -> Result<Vec<u8>, Self::Error> {
+        if let Some(mut ddc) = Commands::new(self, Delays::SPEC) {
+            return ddc.capabilities_string()
+        }
+
         match *self {
             #[cfg(feature = "has-ddc-i2c")]
             Handle::I2cDevice(ref mut i2c) => i2c.capabilities_string().map_err(BackendError::I2cDeviceError),
@@ -599,39 +1991,58 @@ impl Ddc for Handle {
             Handle::MacOS(ref mut monitor) => monitor.capabilities_string().map_err(BackendError::MacOsError),
             #[cfg(feature = "has-nvapi")]
             Handle::Nvapi(ref mut i2c) => i2c.capabilities_string().map_err(BackendError::NvapiError),
+            #[cfg(feature = "testing")]
+            Handle::Snapshot(ref mut snapshot) => return snapshot.capabilities_string(),
         }
         .map_err(Error::CapabilitiesReadError)
     }
 
     fn get_vcp_feature(&mut self, code: FeatureCode) -> Result<VcpValue, Self::Error> {
+        if let Some(mut ddc) = Commands::new(self, Delays::SPEC) {
+            return ddc.get_vcp_feature(code)
+        }
+
         match *self {
             #[cfg(feature = "has-ddc-i2c")]
             Handle::I2cDevice(ref mut i2c) => i2c.get_vcp_feature(code).map_err(BackendError::I2cDeviceError),
             #[cfg(feature = "has-ddc-winapi")]
             Handle::WinApi(ref mut monitor) => monitor.get_vcp_feature(code).map_err(BackendError::WinApiError),
             #[cfg(feature = "has-ddc-macos")]
-            Handle::MacOS(ref mut monitor) => monitor.get_vcp_feature(code).map_err(BackendError::MacOsError),
+            Handle::MacOS(ref mut monitor) => macos::get_vcp_feature(monitor, code).map_err(BackendError::MacOsError),
             #[cfg(feature = "has-nvapi")]
             Handle::Nvapi(ref mut i2c) => i2c.get_vcp_feature(code).map_err(BackendError::NvapiError),
+            #[cfg(feature = "testing")]
+            Handle::Snapshot(ref mut snapshot) => return snapshot.get_vcp_feature(code),
         }
         .map_err(From::from)
     }
 
     fn set_vcp_feature(&mut self, code: FeatureCode, value: u16) -> Result<(), Self::Error> {
+        if let Some(mut ddc) = Commands::new(self, Delays::SPEC) {
+            return ddc.set_vcp_feature(code, value)
+        }
+
         match *self {
             #[cfg(feature = "has-ddc-i2c")]
             Handle::I2cDevice(ref mut i2c) => i2c.set_vcp_feature(code, value).map_err(BackendError::I2cDeviceError),
             #[cfg(feature = "has-ddc-winapi")]
             Handle::WinApi(ref mut monitor) => monitor.set_vcp_feature(code, value).map_err(BackendError::WinApiError),
             #[cfg(feature = "has-ddc-macos")]
-            Handle::MacOS(ref mut monitor) => monitor.set_vcp_feature(code, value).map_err(BackendError::MacOsError),
+            Handle::MacOS(ref mut monitor) =>
+                macos::set_vcp_feature(monitor, code, value).map_err(BackendError::MacOsError),
             #[cfg(feature = "has-nvapi")]
             Handle::Nvapi(ref mut i2c) => i2c.set_vcp_feature(code, value).map_err(BackendError::NvapiError),
+            #[cfg(feature = "testing")]
+            Handle::Snapshot(ref mut snapshot) => return snapshot.set_vcp_feature(code, value),
         }
         .map_err(From::from)
     }
 
     fn save_current_settings(&mut self) -> Result<(), Self::Error> {
+        if let Some(mut ddc) = Commands::new(self, Delays::SPEC) {
+            return ddc.save_current_settings()
+        }
+
         match *self {
             #[cfg(feature = "has-ddc-i2c")]
             Handle::I2cDevice(ref mut i2c) => i2c.save_current_settings().map_err(BackendError::I2cDeviceError),
@@ -641,11 +2052,17 @@ impl Ddc for Handle {
             Handle::MacOS(ref mut monitor) => monitor.save_current_settings().map_err(BackendError::MacOsError),
             #[cfg(feature = "has-nvapi")]
             Handle::Nvapi(ref mut i2c) => i2c.save_current_settings().map_err(BackendError::NvapiError),
+            #[cfg(feature = "testing")]
+            Handle::Snapshot(ref mut snapshot) => return snapshot.save_current_settings(),
         }
         .map_err(From::from)
     }
 
     fn get_timing_report(&mut self) -> Result<TimingMessage, Self::Error> {
+        if let Some(mut ddc) = Commands::new(self, Delays::SPEC) {
+            return ddc.get_timing_report()
+        }
+
         match *self {
             #[cfg(feature = "has-ddc-i2c")]
             Handle::I2cDevice(ref mut i2c) => i2c.get_timing_report().map_err(BackendError::I2cDeviceError),
@@ -655,13 +2072,52 @@ impl Ddc for Handle {
             Handle::MacOS(ref mut monitor) => monitor.get_timing_report().map_err(BackendError::MacOsError),
             #[cfg(feature = "has-nvapi")]
             Handle::Nvapi(ref mut i2c) => i2c.get_timing_report().map_err(BackendError::NvapiError),
+            #[cfg(feature = "testing")]
+            Handle::Snapshot(ref mut snapshot) => return snapshot.get_timing_report(),
         }
         .map_err(From::from)
     }
 }
 
+impl Edid for Handle {
+    type EdidError = Error;
+
+    fn read_edid(&mut self, offset: u8, data: &mut [u8]) -> Result<usize, Self::EdidError> {
+        match *self {
+            #[cfg(feature = "has-ddc-i2c")]
+            Handle::I2cDevice(ref mut i2c) => i2c
+                .read_edid(offset, data)
+                .map_err(|e| Error::LowLevelError(BackendError::I2cDeviceError(ddc_i2c::Error::I2c(e)))),
+            // IOKit keeps a copy of the EDID, so there's no need to read it over I2C
+            #[cfg(feature = "has-ddc-macos")]
+            Handle::MacOS(ref monitor) => {
+                let edid = monitor.edid().ok_or(Error::UnsupportedOp)?;
+                let edid = edid.get(offset as usize..).unwrap_or_default();
+                let len = edid.len().min(data.len());
+                data[..len].copy_from_slice(&edid[..len]);
+                Ok(len)
+            },
+            #[cfg(feature = "has-ddc-winapi")]
+            Handle::WinApi(_) => {
+                let _ = (offset, data);
+                Err(Error::UnsupportedOp)
+            },
+            #[cfg(feature = "has-nvapi")]
+            Handle::Nvapi(ref mut i2c) => i2c
+                .read_edid(offset, data)
+                .map_err(|e| Error::LowLevelError(BackendError::NvapiError(ddc_i2c::Error::I2c(e)))),
+            #[cfg(feature = "testing")]
+            Handle::Snapshot(ref mut snapshot) => snapshot.read_edid(offset, data),
+        }
+    }
+}
+
 impl DdcTable for Handle {
     fn table_read(&mut self, code: FeatureCode) -> Result<Vec<u8>, Self::Error> {
+        if let Some(mut ddc) = Commands::new(self, Delays::SPEC) {
+            return ddc.table_read(code)
+        }
+
         match *self {
             #[cfg(feature = "has-ddc-i2c")]
             Handle::I2cDevice(ref mut i2c) => i2c
@@ -677,10 +2133,16 @@ impl DdcTable for Handle {
             Handle::Nvapi(ref mut i2c) => i2c
                 .table_read(code)
                 .map_err(|e| Error::LowLevelError(BackendError::NvapiError(e))),
+            #[cfg(feature = "testing")]
+            Handle::Snapshot(ref mut snapshot) => snapshot.table_read(code),
         }
     }
 
     fn table_write(&mut self, code: FeatureCode, offset: u16, value: &[u8]) -> Result<(), Self::Error> {
+        if let Some(mut ddc) = Commands::new(self, Delays::SPEC) {
+            return ddc.table_write(code, offset, value)
+        }
+
         match *self {
             #[cfg(feature = "has-ddc-i2c")]
             Handle::I2cDevice(ref mut i2c) => i2c
@@ -696,6 +2158,8 @@ impl DdcTable for Handle {
             Handle::Nvapi(ref mut i2c) => i2c
                 .table_write(code, offset, value)
                 .map_err(|e| Error::LowLevelError(BackendError::NvapiError(e))),
+            #[cfg(feature = "testing")]
+            Handle::Snapshot(ref mut snapshot) => snapshot.table_write(code, offset, value),
         }
     }
 }
@@ -0,0 +1,68 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{DisplayInfo, FeatureCode},
+    std::collections::BTreeMap,
+};
+
+/// Translated names of features and their values.
+///
+/// Names that have no translation fall back to the English names of the
+/// display's MCCS database.
+///
+/// # Example
+///
+/// ```rust
+/// use ddc_hi::{Backend, DisplayInfo, Translations};
+///
+/// let info = DisplayInfo::new(Backend::I2cDevice, "0".into());
+/// let german = Translations::new()
+///     .feature(0x10, "Helligkeit")
+///     .value(0x60, 0x11, "HDMI 1");
+/// assert_eq!(german.feature_name(&info, 0x10), Some("Helligkeit"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Translations {
+    /// The translated names of features.
+    pub features: BTreeMap<FeatureCode, String>,
+    /// The translated names of non-continuous feature values, by feature.
+    pub values: BTreeMap<FeatureCode, BTreeMap<u8, String>>,
+}
+
+impl Translations {
+    /// Create an empty set of translations.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Translate the name of a feature.
+    pub fn feature<S: Into<String>>(mut self, code: FeatureCode, name: S) -> Self {
+        self.features.insert(code, name.into());
+        self
+    }
+
+    /// Translate the name of a non-continuous feature value.
+    pub fn value<S: Into<String>>(mut self, code: FeatureCode, value: u8, name: S) -> Self {
+        self.values.entry(code).or_default().insert(value, name.into());
+        self
+    }
+
+    /// The name of a feature of the display, translated if possible.
+    pub fn feature_name<'a>(&'a self, info: &'a DisplayInfo, code: FeatureCode) -> Option<&'a str> {
+        self.features
+            .get(&code)
+            .map(String::as_str)
+            .or_else(|| info.mccs_database.get(code)?.name.as_deref())
+    }
+
+    /// The name of a non-continuous feature value of the display, translated
+    /// if possible.
+    pub fn value_name<'a>(&'a self, info: &'a DisplayInfo, code: FeatureCode, value: u16) -> Option<&'a str> {
+        self.values
+            .get(&code)
+            .and_then(|values| values.get(&(value as u8)))
+            .map(String::as_str)
+            .or_else(|| info.value_name(code, value))
+    }
+}
@@ -0,0 +1,116 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{Display, Error, FeatureCode},
+    ddc::ErrorCode,
+    log::trace,
+};
+
+/// The dimensions of a display's lookup tables, as reported by the LUT Size
+/// feature (`0x73`).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LutSize {
+    /// The number of entries in the red, green, and blue tables.
+    pub entries: [u16; 3],
+    /// The number of bits per entry in the red, green, and blue tables.
+    pub bits: [u8; 3],
+}
+
+impl LutSize {
+    /// Decode the raw LUT Size table.
+    pub fn decode(table: &[u8]) -> Result<Self, Error> {
+        if table.len() < 9 {
+            return Err(Error::InvalidResponse(ErrorCode::InvalidLength))
+        }
+
+        let entries = |i: usize| u16::from_be_bytes([table[i * 2], table[i * 2 + 1]]);
+        Ok(LutSize {
+            entries: [entries(0), entries(1), entries(2)],
+            bits: [table[6], table[7], table[8]],
+        })
+    }
+}
+
+/// Decode a raw table of interleaved red, green, and blue big-endian 16-bit
+/// entries into the red, green, and blue curves.
+pub fn decode_lut(table: &[u8]) -> Result<[Vec<u16>; 3], Error> {
+    let entries = table.chunks_exact(6);
+    if !entries.remainder().is_empty() {
+        return Err(Error::InvalidResponse(ErrorCode::InvalidLength))
+    }
+
+    let mut curves: [Vec<u16>; 3] = Default::default();
+    for entry in entries {
+        for (curve, value) in curves.iter_mut().zip(entry.chunks_exact(2)) {
+            curve.push(u16::from_be_bytes([value[0], value[1]]));
+        }
+    }
+    Ok(curves)
+}
+
+/// Encode the red, green, and blue curves into the raw table layout of
+/// interleaved big-endian 16-bit entries.
+///
+/// Fails with `Error::InvalidArgument` unless the curves are the same length.
+pub fn encode_lut(curves: &[Vec<u16>; 3]) -> Result<Vec<u8>, Error> {
+    let [red, green, blue] = curves;
+    if red.len() != green.len() || red.len() != blue.len() {
+        return Err(Error::InvalidArgument(format!(
+            "LUT curves have different lengths: {}, {}, {}",
+            red.len(),
+            green.len(),
+            blue.len()
+        )))
+    }
+
+    Ok(red
+        .iter()
+        .zip(green)
+        .zip(blue)
+        .flat_map(|((r, g), b)| [r, g, b])
+        .flat_map(|entry| entry.to_be_bytes())
+        .collect())
+}
+
+impl Display {
+    /// Read the dimensions of the display's lookup tables.
+    pub fn lut_size(&mut self) -> Result<LutSize, Error> {
+        trace!("Display::lut_size");
+
+        LutSize::decode(&self.read_table(0x73, None)?)
+    }
+
+    /// Read a lookup table feature as red, green, and blue curves of 16-bit
+    /// entries.
+    pub fn read_lut(&mut self, code: FeatureCode) -> Result<[Vec<u16>; 3], Error> {
+        decode_lut(&self.read_table(code, None)?)
+    }
+
+    /// Write red, green, and blue curves of 16-bit entries to a lookup table
+    /// feature.
+    pub fn write_lut(&mut self, code: FeatureCode, curves: &[Vec<u16>; 3]) -> Result<(), Error> {
+        self.write_table(code, &encode_lut(curves)?, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lut_channels_round_trip() {
+        let table = [0x00, 0x01, 0x00, 0x02, 0x00, 0x03, 0xff, 0xf1, 0xff, 0xf2, 0xff, 0xf3];
+        let curves = decode_lut(&table).unwrap();
+        assert_eq!(curves, [vec![0x0001, 0xfff1], vec![0x0002, 0xfff2], vec![
+            0x0003, 0xfff3
+        ]]);
+        assert_eq!(encode_lut(&curves).unwrap(), table);
+    }
+
+    #[test]
+    fn lut_partial_entry() {
+        assert!(decode_lut(&[0; 4]).is_err());
+        assert!(encode_lut(&[vec![0; 2], vec![0; 2], vec![0; 1]]).is_err());
+    }
+}
@@ -0,0 +1,162 @@
+use {
+    crate::{
+        Backend, BackendError, Ddc, Display, DisplayAdapter, DisplayBounds, DisplayInfo, Error, FeatureCode, Handle,
+        VcpValue,
+    },
+    log::trace,
+};
+
+/// Create a display from a macOS monitor, identified by its EDID if it has
+/// one.
+pub(crate) fn display(ddc: ddc_macos::Monitor) -> Display {
+    let mut info = ddc
+        .edid()
+        .and_then(|edid| DisplayInfo::from_edid(Backend::MacOS, ddc.description(), edid).ok())
+        .unwrap_or(DisplayInfo::new(Backend::MacOS, ddc.description()));
+
+    let display = ddc.handle();
+    let bounds = display.bounds();
+    info.core_graphics_id = Some(display.id);
+    info.bounds = Some(DisplayBounds {
+        x: bounds.origin.x as i32,
+        y: bounds.origin.y as i32,
+        width: bounds.size.width as u32,
+        height: bounds.size.height as u32,
+    });
+    info.is_internal = Some(display.is_builtin());
+    info.adapter = Some(DisplayAdapter {
+        mirrored: display.is_in_mirror_set(),
+        rotation: Some(display.rotation() as u16),
+        ..Default::default()
+    });
+    Display::from_handle(Handle::MacOS(ddc), info)
+}
+
+impl Display {
+    /// Open the macOS display with a CoreGraphics display ID, as returned by
+    /// `CGGetActiveDisplayList` or `CGMainDisplayID`.
+    pub fn open_macos(display_id: u32) -> Result<Self, Error> {
+        trace!("Display::open_macos({})", display_id);
+
+        ddc_macos::Monitor::enumerate()
+            .map_err(BackendError::MacOsError)?
+            .into_iter()
+            .find(|ddc| ddc.handle().id == display_id)
+            .map(display)
+            .ok_or_else(|| Error::InvalidArgument(format!("no macOS display with ID {}", display_id)))
+    }
+
+    /// Wrap a monitor that was found elsewhere, identifying it in the same way
+    /// as during enumeration.
+    pub fn from_macos(monitor: ddc_macos::Monitor) -> Self {
+        trace!("Display::from_macos({})", monitor);
+
+        display(monitor)
+    }
+}
+
+/// Read a feature, falling back to DisplayServices for the brightness of
+/// displays that don't support DDC/CI with the `display-services` feature.
+pub(crate) fn get_vcp_feature(
+    monitor: &mut ddc_macos::Monitor,
+    code: FeatureCode,
+) -> Result<VcpValue, ddc_macos::Error> {
+    let res = monitor.get_vcp_feature(code);
+    #[cfg(feature = "display-services")]
+    if res.is_err() && code == display_services::BRIGHTNESS {
+        if let Some(brightness) = display_services::brightness(monitor.handle().id) {
+            let value = (brightness * display_services::MAXIMUM as f32).round() as u16;
+            return Ok(VcpValue {
+                ty: 0,
+                mh: 0,
+                ml: display_services::MAXIMUM as u8,
+                sh: (value >> 8) as u8,
+                sl: value as u8,
+            })
+        }
+    }
+    res
+}
+
+/// Write a feature, falling back to DisplayServices for the brightness of
+/// displays that don't support DDC/CI with the `display-services` feature.
+pub(crate) fn set_vcp_feature(
+    monitor: &mut ddc_macos::Monitor,
+    code: FeatureCode,
+    value: u16,
+) -> Result<(), ddc_macos::Error> {
+    let res = monitor.set_vcp_feature(code, value);
+    #[cfg(feature = "display-services")]
+    if res.is_err() && code == display_services::BRIGHTNESS {
+        let brightness = value.min(display_services::MAXIMUM) as f32 / display_services::MAXIMUM as f32;
+        if display_services::set_brightness(monitor.handle().id, brightness) {
+            return Ok(())
+        }
+    }
+    res
+}
+
+/// The private DisplayServices framework, which controls the brightness of
+/// Apple and built-in displays that reject DDC/CI.
+///
+/// It is loaded at runtime because private frameworks can't be linked
+/// against normally, and may be missing or change between macOS releases.
+#[cfg(feature = "display-services")]
+mod display_services {
+    use {
+        crate::FeatureCode,
+        std::os::raw::{c_char, c_int, c_void},
+    };
+
+    pub const BRIGHTNESS: FeatureCode = 0x10;
+    /// Brightness is reported out of this maximum, as a percentage.
+    pub const MAXIMUM: u16 = 100;
+
+    const PATH: &[u8] = b"/System/Library/PrivateFrameworks/DisplayServices.framework/DisplayServices\0";
+    const RTLD_LAZY: c_int = 0x1;
+
+    extern "C" {
+        fn dlopen(path: *const c_char, mode: c_int) -> *mut c_void;
+        fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    }
+
+    type GetBrightness = unsafe extern "C" fn(display: u32, brightness: *mut f32) -> c_int;
+    type SetBrightness = unsafe extern "C" fn(display: u32, brightness: f32) -> c_int;
+
+    fn symbol(name: &[u8]) -> Option<*mut c_void> {
+        unsafe {
+            // the framework stays loaded, so there's no need to close it
+            let framework = dlopen(PATH.as_ptr() as *const c_char, RTLD_LAZY);
+            if framework.is_null() {
+                return None
+            }
+            let symbol = dlsym(framework, name.as_ptr() as *const c_char);
+            (!symbol.is_null()).then_some(symbol)
+        }
+    }
+
+    /// The brightness of a display, between 0 and 1.
+    pub fn brightness(display: u32) -> Option<f32> {
+        let get = symbol(b"DisplayServicesGetBrightness\0")?;
+        unsafe {
+            let get: GetBrightness = std::mem::transmute(get);
+            let mut brightness = 0.0;
+            match get(display, &mut brightness) {
+                0 => Some(brightness),
+                _ => None,
+            }
+        }
+    }
+
+    /// Change the brightness of a display, between 0 and 1.
+    pub fn set_brightness(display: u32, brightness: f32) -> bool {
+        let set = match symbol(b"DisplayServicesSetBrightness\0") {
+            Some(set) => set,
+            None => return false,
+        };
+        unsafe {
+            let set: SetBrightness = std::mem::transmute(set);
+            set(display, brightness) == 0
+        }
+    }
+}
@@ -0,0 +1,51 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use {crate::DisplayInfo, std::fmt};
+
+/// When a display was manufactured, decoded from the raw EDID
+/// `manufacture_week` and `manufacture_year` bytes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ManufactureDate {
+    /// The year, such as `2019`.
+    pub year: u16,
+    /// The week of the year from 1 to 54, if specified.
+    pub week: Option<u8>,
+    /// Whether `year` is the model year rather than the year of
+    /// manufacture.
+    pub model_year: bool,
+}
+
+impl ManufactureDate {
+    /// Decode the EDID week and year bytes.
+    ///
+    /// The year is stored as an offset from 1990. A week of `0xff` marks it
+    /// as the model year, and a week of `0` or beyond 54 leaves the week
+    /// unspecified.
+    pub fn from_edid(week: u8, year: u8) -> Self {
+        ManufactureDate {
+            year: 1990 + year as u16,
+            week: Some(week).filter(|week| (1..=54).contains(week)),
+            model_year: week == 0xff,
+        }
+    }
+}
+
+impl fmt::Display for ManufactureDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.model_year, self.week) {
+            (true, _) => write!(f, "model year {}", self.year),
+            (false, Some(week)) => write!(f, "week {} of {}", week, self.year),
+            (false, None) => write!(f, "{}", self.year),
+        }
+    }
+}
+
+impl DisplayInfo {
+    /// When the display was manufactured, decoded from `manufacture_year`
+    /// and `manufacture_week`.
+    pub fn manufacture_date(&self) -> Option<ManufactureDate> {
+        self.manufacture_year
+            .map(|year| ManufactureDate::from_edid(self.manufacture_week.unwrap_or(0), year))
+    }
+}
@@ -0,0 +1,168 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{Display, Operation},
+    log::trace,
+    std::{
+        collections::BTreeMap,
+        sync::atomic::{AtomicU64, Ordering},
+        time::Duration,
+    },
+};
+
+static DISPLAYS_DETECTED: AtomicU64 = AtomicU64::new(0);
+static OPERATIONS: AtomicU64 = AtomicU64::new(0);
+static FAILURES: AtomicU64 = AtomicU64::new(0);
+static RETRIES: AtomicU64 = AtomicU64::new(0);
+
+/// Process-wide counters across every `Display`, suitable for exporting to a
+/// monitoring system.
+///
+/// These are always collected, regardless of `Display::set_metrics_enabled`.
+/// With the `metrics` feature they are also reported through the `metrics`
+/// crate as `ddc_hi_displays_detected`, `ddc_hi_operations_total`,
+/// `ddc_hi_failures_total` and `ddc_hi_retries_total`, labelled by operation.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Stats {
+    /// The number of displays found by the most recent enumeration.
+    pub displays_detected: u64,
+    /// The number of operations performed.
+    pub operations: u64,
+    /// The number of operations that ultimately failed.
+    pub failures: u64,
+    /// The number of retries made, not counting each operation's first attempt.
+    pub retries: u64,
+}
+
+impl Stats {
+    /// The current values of the counters.
+    pub fn snapshot() -> Self {
+        Stats {
+            displays_detected: DISPLAYS_DETECTED.load(Ordering::Relaxed),
+            operations: OPERATIONS.load(Ordering::Relaxed),
+            failures: FAILURES.load(Ordering::Relaxed),
+            retries: RETRIES.load(Ordering::Relaxed),
+        }
+    }
+}
+
+pub(crate) fn record_detected(displays: usize) {
+    DISPLAYS_DETECTED.store(displays as u64, Ordering::Relaxed);
+
+    #[cfg(feature = "metrics")]
+    ::metrics::gauge!("ddc_hi_displays_detected").set(displays as f64);
+}
+
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn record_operation(op: Operation, attempts: u32, failed: bool) {
+    let retries = attempts.saturating_sub(1) as u64;
+    OPERATIONS.fetch_add(1, Ordering::Relaxed);
+    FAILURES.fetch_add(failed as u64, Ordering::Relaxed);
+    RETRIES.fetch_add(retries, Ordering::Relaxed);
+
+    #[cfg(feature = "metrics")]
+    {
+        let op = format!("{:?}", op);
+        ::metrics::counter!("ddc_hi_operations_total", "operation" => op.clone()).increment(1);
+        ::metrics::counter!("ddc_hi_failures_total", "operation" => op.clone()).increment(failed as u64);
+        ::metrics::counter!("ddc_hi_retries_total", "operation" => op).increment(retries);
+    }
+}
+
+/// Statistics collected for one kind of operation.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OperationMetrics {
+    /// The number of times the operation was performed.
+    pub calls: u64,
+    /// The number of calls that ultimately failed.
+    pub failures: u64,
+    /// The number of retries made, not counting each call's first attempt.
+    pub retries: u64,
+    /// The total time spent in the operation, including retries.
+    pub total_time: Duration,
+    /// The longest time a single call took.
+    pub max_time: Duration,
+}
+
+impl OperationMetrics {
+    /// The average time a call took.
+    pub fn mean_time(&self) -> Option<Duration> {
+        match self.calls {
+            0 => None,
+            calls => Some(self.total_time / calls as u32),
+        }
+    }
+
+    /// The fraction of calls that failed, from `0.0` to `1.0`.
+    pub fn failure_rate(&self) -> f64 {
+        match self.calls {
+            0 => 0.0,
+            calls => self.failures as f64 / calls as f64,
+        }
+    }
+}
+
+/// Per-operation statistics for a `Display`.
+///
+/// Collection is disabled by default; see `Display::set_metrics_enabled`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Metrics {
+    operations: BTreeMap<Operation, OperationMetrics>,
+}
+
+impl Metrics {
+    /// The statistics for an operation, if it has been performed.
+    pub fn get(&self, op: Operation) -> Option<&OperationMetrics> {
+        self.operations.get(&op)
+    }
+
+    /// The statistics for every operation that has been performed.
+    pub fn iter(&self) -> impl Iterator<Item = (Operation, &OperationMetrics)> {
+        self.operations.iter().map(|(&op, metrics)| (op, metrics))
+    }
+
+    pub(crate) fn record(&mut self, op: Operation, elapsed: Duration, attempts: u32, failed: bool) {
+        trace!(
+            "{:?} took {:?} over {} attempt(s){}",
+            op,
+            elapsed,
+            attempts,
+            if failed { " and failed" } else { "" }
+        );
+
+        let metrics = self.operations.entry(op).or_default();
+        metrics.calls += 1;
+        metrics.failures += failed as u64;
+        metrics.retries += attempts.saturating_sub(1) as u64;
+        metrics.total_time += elapsed;
+        metrics.max_time = metrics.max_time.max(elapsed);
+    }
+}
+
+impl Display {
+    /// The statistics collected so far, if collection is enabled.
+    pub fn metrics(&self) -> Option<&Metrics> {
+        self.metrics.as_ref()
+    }
+
+    /// Enable or disable collecting operation statistics.
+    ///
+    /// Disabling collection discards any statistics collected so far.
+    pub fn set_metrics_enabled(&mut self, enabled: bool) {
+        if !enabled {
+            self.metrics = None;
+        } else if self.metrics.is_none() {
+            self.metrics = Some(Default::default());
+        }
+    }
+
+    /// Discard the statistics collected so far.
+    pub fn reset_metrics(&mut self) {
+        if let Some(metrics) = self.metrics.as_mut() {
+            *metrics = Default::default();
+        }
+    }
+}
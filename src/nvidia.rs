@@ -0,0 +1,176 @@
+use {
+    crate::{Backend, Display, DisplayInfo, Error, Handle},
+    ddc::{Ddc, Edid},
+    log::{debug, trace},
+    nvapi::{
+        sys::{self, handles::NvPhysicalGpuHandle, i2c::I2cSpeed, Api, NvAPI_Status},
+        I2c, PhysicalGpu,
+    },
+    std::{mem, rc::Rc},
+};
+
+/// Ports to try, in order, when a display doesn't reply on the default one.
+///
+/// Some cards with several outputs only reach the display through port 1.
+const FALLBACK_PORTS: [u8; 3] = [1, 2, 3];
+
+const EDID_DATA_SIZE: usize = 0x100;
+
+#[repr(C)]
+struct NvEdidV3 {
+    version: u32,
+    edid_data: [u8; EDID_DATA_SIZE],
+    sizeof_edid: u32,
+    edid_id: u32,
+    offset: u32,
+}
+
+const NV_EDID_VER3: u32 = mem::size_of::<NvEdidV3>() as u32 | 3 << 16;
+
+// the handle is a bare pointer, it just lacks `repr(transparent)`
+#[allow(improper_ctypes_definitions)]
+type GetEdidFn = extern "C" fn(gpu: NvPhysicalGpuHandle, display_id: u32, edid: *mut NvEdidV3) -> NvAPI_Status;
+
+/// Read the EDID of a display, preferring the copy cached by the driver and
+/// falling back to reading it over I2C.
+pub(crate) fn read_edid(
+    gpu: &PhysicalGpu,
+    display_id: u32,
+    ddc: &mut ddc_i2c::I2cDdc<I2c<Rc<PhysicalGpu>>>,
+    id: &str,
+) -> sys::Result<Vec<u8>> {
+    read_driver_edid(gpu, display_id).or_else(|e| {
+        debug!("Falling back to reading the EDID of {} over I2C: {}", id, e);
+        read_i2c_edid(ddc, id)
+    })
+}
+
+/// Read the EDID that the driver keeps for a display.
+///
+/// This is not wrapped by the nvapi crate, so it is looked up directly.
+fn read_driver_edid(gpu: &PhysicalGpu, display_id: u32) -> sys::Result<Vec<u8>> {
+    let get_edid = sys::nvapi_QueryInterface(Api::NvAPI_GPU_GetEDID.id())?;
+    let get_edid: GetEdidFn = unsafe { mem::transmute(get_edid) };
+
+    let mut edid = Vec::new();
+    let mut request = NvEdidV3 {
+        version: NV_EDID_VER3,
+        edid_data: [0; EDID_DATA_SIZE],
+        sizeof_edid: 0,
+        edid_id: 0,
+        offset: 0,
+    };
+    loop {
+        // the id from the first read makes sure later chunks are of the same EDID
+        request.offset = edid.len() as u32;
+        sys::status_result(get_edid(*gpu.handle(), display_id, &mut request))?;
+
+        let total = request.sizeof_edid as usize;
+        let len = total.saturating_sub(edid.len()).min(EDID_DATA_SIZE);
+        if len == 0 {
+            break
+        }
+        edid.extend_from_slice(&request.edid_data[..len]);
+        if edid.len() >= total {
+            break
+        }
+    }
+
+    match edid.is_empty() {
+        true => Err(sys::Status::NotSupported),
+        false => Ok(edid),
+    }
+}
+
+fn read_i2c_edid(ddc: &mut ddc_i2c::I2cDdc<I2c<Rc<PhysicalGpu>>>, id: &str) -> sys::Result<Vec<u8>> {
+    // hack around broken nvidia drivers
+    // the register argument doesn't seem to work at all
+    // so write the edid eeprom offset here first
+    ddc.inner_mut().set_address(0x50);
+    let _ = ddc.inner_mut().nvapi_write(&[], &[0]);
+
+    let mut edid = vec![0u8; 0x80];
+    ddc.read_edid(0, &mut edid)?;
+    // names and serial numbers may be in the extension block,
+    // but any past the first need E-DDC segments that NVAPI can't address
+    if edid[0x7e] > 0 {
+        edid.resize(0x100, 0);
+        if let Err(e) = ddc.read_edid(0x80, &mut edid[0x80..]) {
+            debug!("Failed to read EDID extension of {}: {}", id, e);
+            edid.truncate(0x80);
+        }
+    }
+
+    Ok(edid)
+}
+
+/// Find an I2C port that the display replies to DDC/CI commands on.
+///
+/// The default port is kept if none of them reply.
+pub(crate) fn select_port(ddc: &mut ddc_i2c::I2cDdc<I2c<Rc<PhysicalGpu>>>, id: &str) {
+    if ddc.get_vcp_feature(0xdf).is_ok() {
+        return
+    }
+
+    for &port in &FALLBACK_PORTS {
+        ddc.inner_mut().set_port(Some(port), true);
+        if ddc.get_vcp_feature(0xdf).is_ok() {
+            debug!("Using I2C port {} for {}", port, id);
+            return
+        }
+    }
+
+    ddc.inner_mut().set_port(None, true);
+}
+
+impl Display {
+    /// Change the I2C port used to communicate with an NVAPI display.
+    ///
+    /// `None` lets the driver choose. `port_is_ddc` selects between the DDC
+    /// ports and the card's other I2C buses.
+    pub fn set_nvapi_port(&mut self, port: Option<u8>, port_is_ddc: bool) -> Result<(), Error> {
+        trace!("Display::set_nvapi_port({:?}, {})", port, port_is_ddc);
+
+        match self.handle {
+            Handle::Nvapi(ref mut ddc) => {
+                ddc.inner_mut().set_port(port, port_is_ddc);
+                Ok(())
+            },
+            #[allow(unreachable_patterns)]
+            _ => Err(Error::UnsupportedOp),
+        }
+    }
+
+    /// Wrap an NVAPI I2C bus that was set up elsewhere.
+    ///
+    /// The display is identified from the EDID read over the bus, and `id`
+    /// is used if that fails.
+    pub fn from_nvapi(i2c: I2c<Rc<PhysicalGpu>>, id: String) -> Self {
+        trace!("Display::from_nvapi({})", id);
+
+        let mut ddc = ddc_i2c::I2cDdc::new(i2c);
+        let info = read_i2c_edid(&mut ddc, &id)
+            .map_err(|e| e.to_string())
+            .and_then(|edid| DisplayInfo::from_edid(Backend::Nvapi, id.clone(), edid).map_err(|e| e.to_string()))
+            .unwrap_or_else(|e| {
+                debug!("Failed to identify {}: {}", id, e);
+                DisplayInfo::new(Backend::Nvapi, id)
+            });
+
+        Display::from_handle(Handle::Nvapi(ddc), info)
+    }
+
+    /// Change the I2C bus speed used to communicate with an NVAPI display.
+    pub fn set_nvapi_speed(&mut self, speed: I2cSpeed) -> Result<(), Error> {
+        trace!("Display::set_nvapi_speed({})", speed);
+
+        match self.handle {
+            Handle::Nvapi(ref mut ddc) => {
+                ddc.inner_mut().set_speed(speed);
+                Ok(())
+            },
+            #[allow(unreachable_patterns)]
+            _ => Err(Error::UnsupportedOp),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{Ddc, Display, DisplayInfo, Error, FeatureCode},
+    log::trace,
+};
+
+/// The screen orientation feature.
+const SCREEN_ORIENTATION: FeatureCode = 0xaa;
+
+/// How far a display is rotated clockwise.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Orientation {
+    /// Not rotated.
+    Landscape,
+    /// Rotated by 90 degrees.
+    Portrait,
+    /// Rotated by 180 degrees.
+    LandscapeFlipped,
+    /// Rotated by 270 degrees.
+    PortraitFlipped,
+}
+
+impl Orientation {
+    /// The orientation for a clockwise rotation in degrees, if it is a
+    /// multiple of 90.
+    pub fn from_degrees(degrees: u16) -> Option<Self> {
+        Some(match degrees % 360 {
+            0 => Orientation::Landscape,
+            90 => Orientation::Portrait,
+            180 => Orientation::LandscapeFlipped,
+            270 => Orientation::PortraitFlipped,
+            _ => return None,
+        })
+    }
+
+    /// The clockwise rotation in degrees.
+    pub fn degrees(&self) -> u16 {
+        match *self {
+            Orientation::Landscape => 0,
+            Orientation::Portrait => 90,
+            Orientation::LandscapeFlipped => 180,
+            Orientation::PortraitFlipped => 270,
+        }
+    }
+}
+
+impl DisplayInfo {
+    /// The orientation configured by the operating system, if the backend
+    /// reports it.
+    pub fn orientation(&self) -> Option<Orientation> {
+        self.adapter
+            .as_ref()
+            .and_then(|adapter| adapter.rotation)
+            .and_then(Orientation::from_degrees)
+    }
+}
+
+impl Display {
+    /// Read the physical orientation reported by the display through the
+    /// screen orientation feature (`0xaa`).
+    ///
+    /// Returns `None` if the display can't sense its orientation. Compare
+    /// with `DisplayInfo::orientation` to check that the configured rotation
+    /// matches how the display is mounted.
+    pub fn orientation(&mut self) -> Result<Option<Orientation>, Error> {
+        trace!("Display::orientation");
+
+        Ok(match self.get_vcp_feature(SCREEN_ORIENTATION)?.sl {
+            0x01 => Some(Orientation::Landscape),
+            0x02 => Some(Orientation::Portrait),
+            0x03 => Some(Orientation::LandscapeFlipped),
+            0x04 => Some(Orientation::PortraitFlipped),
+            _ => None,
+        })
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{Backend, DisplayInfo, Error, ErrorKind};
+#[cfg(feature = "has-ddc-i2c")]
+use {
+    crate::{drm, warning, BackendError, Display, Handle},
+    log::{debug, trace},
+    std::{fs, io, path::Path},
+};
+
+const I2C_HINT: &str = "add the user to the group that owns /dev/i2c-* (usually `i2c`), or install a udev rule \
+                        such as `KERNEL==\"i2c-[0-9]*\", GROUP=\"i2c\", MODE=\"0660\"`";
+
+const ELEVATION_HINT: &str = "run the process as an administrator";
+
+const OTHER_HINT: &str = "check the permissions of the display's device";
+
+impl Error {
+    /// Describe a permission failure along with how it may be fixed.
+    pub(crate) fn permission_denied(backend: Backend, path: String) -> Self {
+        Error::PermissionDenied {
+            path,
+            hint: match backend {
+                Backend::I2cDevice => I2C_HINT,
+                Backend::WinApi | Backend::Nvapi | Backend::MacOS => ELEVATION_HINT,
+                Backend::Other(_) => OTHER_HINT,
+            },
+        }
+    }
+
+    /// Replace a backend permission failure with `Error::PermissionDenied`.
+    pub(crate) fn with_permission_hint(self, backend: Backend, path: &str) -> Self {
+        match self {
+            e @ Error::PermissionDenied { .. } => e,
+            e if e.kind() == ErrorKind::PermissionDenied => Error::permission_denied(backend, path.into()),
+            e => e,
+        }
+    }
+}
+
+impl DisplayInfo {
+    /// The path of the device used to communicate with the display, for
+    /// permission errors.
+    ///
+    /// This is the `/dev/i2c-*` device for the i2c-dev backend, and the
+    /// display's ID otherwise.
+    pub(crate) fn device_path(&self) -> String {
+        match self.backend {
+            #[cfg(feature = "has-ddc-i2c")]
+            Backend::I2cDevice => match self.id.parse() {
+                Ok(rdev) => format!("/dev/i2c-{}", drm::i2c_bus(rdev)),
+                Err(_) => self.id.clone(),
+            },
+            _ => self.id.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "has-ddc-i2c")]
+impl Display {
+    /// Open the display attached to an I2C device, such as `/dev/i2c-4`.
+    ///
+    /// Fails with `Error::PermissionDenied` if the device's permissions don't
+    /// allow it to be opened.
+    pub fn open_i2c<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        trace!("Display::open_i2c({})", path.display());
+
+        let ddc = ddc_i2c::from_i2c_device(path).map_err(|e| match e.kind() {
+            io::ErrorKind::PermissionDenied => Error::permission_denied(Backend::I2cDevice, path.display().to_string()),
+            _ => BackendError::I2cDeviceError(ddc_i2c::Error::I2c(e)).into(),
+        })?;
+
+        Ok(Display::from_i2c(ddc))
+    }
+
+    /// Wrap an I2C device that was opened elsewhere, such as one inherited
+    /// from another process.
+    ///
+    /// The display is identified from its EDID in the same way as during
+    /// enumeration.
+    pub fn from_i2c(mut ddc: ddc_i2c::I2cDeviceDdc) -> Self {
+        use std::os::unix::fs::MetadataExt;
+
+        trace!("Display::from_i2c");
+
+        let rdev = ddc
+            .inner_ref()
+            .inner_ref()
+            .metadata()
+            .map(|meta| meta.rdev())
+            .unwrap_or_default();
+        let connector = drm::connectors().remove(&drm::i2c_bus(rdev));
+        let info = drm::display_info(&mut ddc, rdev, connector.as_ref()).unwrap_or_else(|e| {
+            debug!("{}", e);
+            let mut info = DisplayInfo::new(Backend::I2cDevice, rdev.to_string());
+            if let Some(ref connector) = connector {
+                connector.fill(&mut info);
+            }
+            info
+        });
+
+        Display::from_handle(Handle::I2cDevice(ddc), info)
+    }
+}
+
+/// Warn about any I2C devices that enumeration skipped because they couldn't
+/// be opened.
+#[cfg(feature = "has-ddc-i2c")]
+pub(crate) fn warn_inaccessible_i2c() {
+    let entries = match fs::read_dir("/dev") {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_i2c = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("i2c-"));
+        if !is_i2c {
+            continue
+        }
+
+        if let Err(e) = fs::File::open(&path) {
+            if e.kind() == io::ErrorKind::PermissionDenied {
+                warning::failure(
+                    Backend::I2cDevice,
+                    Error::permission_denied(Backend::I2cDevice, path.display().to_string()).to_string(),
+                );
+            }
+        }
+    }
+}
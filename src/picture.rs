@@ -0,0 +1,117 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{Ddc, Display, DisplayInfo, Error, FeatureCode},
+    log::trace,
+};
+
+/// The display application feature, which selects a picture mode.
+pub const DISPLAY_APPLICATION: FeatureCode = 0xdc;
+
+/// A picture mode selected through the display application feature
+/// (`0xdc`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DisplayApplication {
+    /// The standard or default mode.
+    Standard,
+    /// Productivity, such as office or word processing.
+    Productivity,
+    /// Mixed, such as internet browsing.
+    Mixed,
+    /// Movies.
+    Movie,
+    /// A mode configured by the user.
+    UserDefined,
+    /// Games.
+    Games,
+    /// Sports, with fast motion.
+    Sports,
+    /// Professional use, with all signal processing disabled.
+    Professional,
+    /// The standard mode with intermediate power consumption.
+    StandardIntermediatePower,
+    /// The standard mode with low power consumption.
+    StandardLowPower,
+    /// A demonstration mode.
+    Demonstration,
+    /// Dynamic contrast.
+    DynamicContrast,
+    /// A value not defined by MCCS, often a vendor-specific mode such as
+    /// HDR.
+    Other(u8),
+}
+
+impl DisplayApplication {
+    /// Interpret a display application value.
+    pub fn from_value(value: u8) -> Self {
+        match value {
+            0x00 => DisplayApplication::Standard,
+            0x01 => DisplayApplication::Productivity,
+            0x02 => DisplayApplication::Mixed,
+            0x03 => DisplayApplication::Movie,
+            0x04 => DisplayApplication::UserDefined,
+            0x05 => DisplayApplication::Games,
+            0x06 => DisplayApplication::Sports,
+            0x07 => DisplayApplication::Professional,
+            0x08 => DisplayApplication::StandardIntermediatePower,
+            0x09 => DisplayApplication::StandardLowPower,
+            0x0a => DisplayApplication::Demonstration,
+            0xf0 => DisplayApplication::DynamicContrast,
+            value => DisplayApplication::Other(value),
+        }
+    }
+
+    /// The value written to the display application feature.
+    pub fn value(&self) -> u8 {
+        match *self {
+            DisplayApplication::Standard => 0x00,
+            DisplayApplication::Productivity => 0x01,
+            DisplayApplication::Mixed => 0x02,
+            DisplayApplication::Movie => 0x03,
+            DisplayApplication::UserDefined => 0x04,
+            DisplayApplication::Games => 0x05,
+            DisplayApplication::Sports => 0x06,
+            DisplayApplication::Professional => 0x07,
+            DisplayApplication::StandardIntermediatePower => 0x08,
+            DisplayApplication::StandardLowPower => 0x09,
+            DisplayApplication::Demonstration => 0x0a,
+            DisplayApplication::DynamicContrast => 0xf0,
+            DisplayApplication::Other(value) => value,
+        }
+    }
+}
+
+impl DisplayInfo {
+    /// The picture modes advertised for the display application feature
+    /// (`0xdc`).
+    ///
+    /// This is only accurate once the display's capabilities have been
+    /// read, and is empty if the display doesn't advertise the feature.
+    #[cfg(feature = "mccs-db")]
+    pub fn display_applications(&self) -> Vec<DisplayApplication> {
+        match self.mccs_database.get(DISPLAY_APPLICATION).map(|desc| &desc.ty) {
+            Some(mccs_db::ValueType::NonContinuous { values, .. }) =>
+                values.keys().copied().map(DisplayApplication::from_value).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl Display {
+    /// Read the current picture mode.
+    pub fn display_application(&mut self) -> Result<DisplayApplication, Error> {
+        trace!("Display::display_application");
+
+        Ok(DisplayApplication::from_value(
+            self.get_vcp_feature(DISPLAY_APPLICATION)?.sl,
+        ))
+    }
+
+    /// Change the picture mode.
+    pub fn set_display_application(&mut self, application: DisplayApplication) -> Result<(), Error> {
+        trace!("Display::set_display_application({:?})", application);
+
+        self.set_vcp_feature(DISPLAY_APPLICATION, application.value() as u16)
+    }
+}
@@ -0,0 +1,144 @@
+//! Serving a `Broker` to several clients over a Windows named pipe.
+
+use {
+    crate::{
+        broker::{self, Broker, Request, Response},
+        ErrorKind,
+    },
+    log::{debug, trace},
+    std::{
+        ffi::OsStr,
+        fs::File,
+        io::{self, BufRead, BufReader, Read, Write},
+        os::windows::{ffi::OsStrExt, io::FromRawHandle},
+        ptr,
+        sync::mpsc::{self, Sender},
+        thread,
+    },
+    winapi::{
+        shared::winerror::ERROR_PIPE_CONNECTED,
+        um::{
+            handleapi::INVALID_HANDLE_VALUE,
+            namedpipeapi::{ConnectNamedPipe, CreateNamedPipeW},
+            winbase::{PIPE_ACCESS_DUPLEX, PIPE_READMODE_BYTE, PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT},
+        },
+    },
+};
+
+/// The size of each pipe instance's buffers.
+const PIPE_BUFFER_LEN: u32 = 4096;
+
+/// A request from a client thread and where to send its response.
+type Call = (Request, Sender<Response>);
+
+/// The server end of a connected pipe instance.
+struct Pipe(BufReader<File>);
+
+impl Read for Pipe {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl BufRead for Pipe {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.0.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.0.consume(amt)
+    }
+}
+
+impl Write for Pipe {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.get_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.get_mut().flush()
+    }
+}
+
+/// Create a pipe instance and wait for a client to connect to it.
+fn accept(path: &[u16]) -> io::Result<Pipe> {
+    let handle = unsafe {
+        CreateNamedPipeW(
+            path.as_ptr(),
+            PIPE_ACCESS_DUPLEX,
+            PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT,
+            PIPE_UNLIMITED_INSTANCES,
+            PIPE_BUFFER_LEN,
+            PIPE_BUFFER_LEN,
+            0,
+            ptr::null_mut(),
+        )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+        return Err(io::Error::last_os_error())
+    }
+    let pipe = Pipe(BufReader::new(unsafe { File::from_raw_handle(handle as _) }));
+
+    // a client may already have connected since the instance was created
+    if unsafe { ConnectNamedPipe(handle, ptr::null_mut()) } == 0 {
+        let e = io::Error::last_os_error();
+        if e.raw_os_error() != Some(ERROR_PIPE_CONNECTED as i32) {
+            return Err(e)
+        }
+    }
+
+    Ok(pipe)
+}
+
+/// Serve a connected client, forwarding its requests to the broker.
+fn client(mut pipe: Pipe, calls: Sender<Call>) {
+    let res = broker::serve_lines(&mut pipe, |request| {
+        let (reply, response) = mpsc::channel();
+        calls
+            .send((request.clone(), reply))
+            .ok()
+            .and_then(|()| response.recv().ok())
+            .unwrap_or_else(|| Response::Error {
+                kind: ErrorKind::Other,
+                message: "the broker has stopped".into(),
+            })
+    });
+    if let Err(e) = res {
+        debug!("Broker pipe client failed: {}", e);
+    }
+}
+
+impl Broker {
+    /// Serve requests from any number of clients on a named pipe, such as
+    /// `\\.\pipe\ddc-hi`, using the line protocol.
+    ///
+    /// Each client is served on a thread of its own, while their requests
+    /// are handled one at a time on the calling thread, which keeps sole
+    /// ownership of the monitor handles. The pipe is created with default
+    /// security, so only the same user and administrators can connect. This
+    /// only returns if a pipe instance can't be created or connected.
+    pub fn serve_pipe(&mut self, path: &str) -> io::Result<()> {
+        trace!("Broker::serve_pipe({})", path);
+
+        let path: Vec<u16> = OsStr::new(path).encode_wide().chain(Some(0)).collect();
+        let (calls, requests) = mpsc::channel::<Call>();
+        let listener = thread::spawn(move || loop {
+            match accept(&path) {
+                Ok(pipe) => {
+                    let calls = calls.clone();
+                    thread::spawn(move || client(pipe, calls));
+                },
+                Err(e) => return e,
+            }
+        });
+
+        // ends once the listener and every client have gone away
+        for (request, reply) in requests {
+            let _ = reply.send(self.handle(&request));
+        }
+
+        Err(listener
+            .join()
+            .unwrap_or_else(|_| io::Error::other("the pipe listener panicked")))
+    }
+}
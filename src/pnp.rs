@@ -0,0 +1,24 @@
+//! Names of display manufacturers from their PNP IDs, with the `pnp-ids`
+//! feature.
+
+use crate::DisplayInfo;
+
+// Manufacturer names from the UEFI PNP ID registry, sorted by ID, generated by
+// build.rs from src/pnp.ids.
+include!(concat!(env!("OUT_DIR"), "/pnp_ids.rs"));
+
+/// Look up the name of a manufacturer by its three-character PNP ID.
+pub fn manufacturer_name(id: &str) -> Option<&'static str> {
+    PNP_IDS
+        .binary_search_by_key(&id, |&(id, _)| id)
+        .ok()
+        .map(|i| PNP_IDS[i].1)
+}
+
+impl DisplayInfo {
+    /// The name of the display's manufacturer, such as `Dell Inc.` for
+    /// `DEL`, if its ID is known.
+    pub fn manufacturer_name(&self) -> Option<&'static str> {
+        self.manufacturer_id.as_deref().and_then(manufacturer_name)
+    }
+}
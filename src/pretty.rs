@@ -0,0 +1,37 @@
+use {
+    crate::{DisplayInfo, FeatureCode, VcpValue},
+    mccs_db::ValueType,
+    std::{collections::BTreeMap, fmt},
+};
+
+/// A printable table of a display's features.
+///
+/// Created by `DisplayInfo::feature_table`.
+pub struct FeatureTable<'a> {
+    pub(crate) info: &'a DisplayInfo,
+    pub(crate) values: &'a BTreeMap<FeatureCode, VcpValue>,
+}
+
+impl<'a> fmt::Display for FeatureTable<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for desc in self.info.features() {
+            write!(f, "0x{:02x} {}", desc.code, desc.name.as_deref().unwrap_or("Unknown"))?;
+
+            if let Some(value) = self.values.get(&desc.code) {
+                write!(f, ": {}", self.info.format_value(desc.code, value))?;
+            } else if let ValueType::NonContinuous { ref values, .. } = desc.ty {
+                for (i, (value, name)) in values.iter().enumerate() {
+                    write!(f, "{}", if i == 0 { ": " } else { ", " })?;
+                    match name {
+                        Some(name) => write!(f, "{} (0x{:02x})", name, value)?,
+                        None => write!(f, "0x{:02x}", value)?,
+                    }
+                }
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
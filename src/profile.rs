@@ -0,0 +1,182 @@
+#[cfg(feature = "mccs-db")]
+use mccs_db::{Access, ValueInterpretation, ValueType};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{Backend, Ddc, Display, DisplayInfo, Error, FeatureCode, TransactionError, VcpTransaction},
+    log::{trace, warn},
+    std::collections::BTreeMap,
+};
+
+/// A saved set of VCP feature values along with the identity of the display
+/// they were read from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Profile {
+    /// The backend of the display the profile was taken from.
+    pub backend: Backend,
+    /// The backend-specific identifier of the display.
+    pub id: String,
+    /// A three-character identifier of the manufacturer of the display.
+    pub manufacturer_id: Option<String>,
+    /// A number that identifies the product model.
+    pub model_id: Option<u16>,
+    /// The model name of the display.
+    pub model_name: Option<String>,
+    /// Human-readable serial number of the device.
+    pub serial_number: Option<String>,
+    /// The saved feature values.
+    pub values: BTreeMap<FeatureCode, u16>,
+}
+
+impl Profile {
+    /// Create an empty profile describing the specified display.
+    pub fn new(info: &DisplayInfo) -> Self {
+        Profile {
+            backend: info.backend,
+            id: info.id.clone(),
+            manufacturer_id: info.manufacturer_id.clone(),
+            model_id: info.model_id,
+            model_name: info.model_name.clone(),
+            serial_number: info.serial_number.clone(),
+            values: Default::default(),
+        }
+    }
+
+    /// Whether the profile appears to describe the same model of display.
+    ///
+    /// Only identifying information present in both is compared, so a profile
+    /// always matches a display with no EDID information.
+    pub fn matches(&self, info: &DisplayInfo) -> bool {
+        fn eq<T: PartialEq>(lhs: &Option<T>, rhs: &Option<T>) -> bool {
+            match (lhs, rhs) {
+                (Some(lhs), Some(rhs)) => lhs == rhs,
+                _ => true,
+            }
+        }
+
+        eq(&self.manufacturer_id, &info.manufacturer_id)
+            && eq(&self.model_id, &info.model_id)
+            && eq(&self.model_name, &info.model_name)
+    }
+
+    /// A transaction that writes all of the saved values.
+    pub fn transaction(&self) -> VcpTransaction {
+        self.values
+            .iter()
+            .fold(VcpTransaction::new(), |t, (&code, &value)| t.set(code, value))
+    }
+
+    /// The features whose values differ from those of `other`, ordered by
+    /// code.
+    ///
+    /// This profile is treated as the older of the two, such as a saved
+    /// profile compared with a fresh snapshot of the display.
+    pub fn diff(&self, other: &Profile) -> Vec<ProfileChange> {
+        let mut codes: Vec<_> = self.values.keys().chain(other.values.keys()).copied().collect();
+        codes.sort_unstable();
+        codes.dedup();
+
+        codes
+            .into_iter()
+            .map(|code| ProfileChange {
+                code,
+                old: self.values.get(&code).copied(),
+                new: other.values.get(&code).copied(),
+            })
+            .filter(|change| change.old != change.new)
+            .collect()
+    }
+}
+
+/// A feature whose value differs between two profiles, as returned by
+/// `Profile::diff`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ProfileChange {
+    /// The feature that changed.
+    pub code: FeatureCode,
+    /// The value in the older profile, if it was saved.
+    pub old: Option<u16>,
+    /// The value in the newer profile, if it was saved.
+    pub new: Option<u16>,
+}
+
+/// Whether a feature holds a value that can be read and later written back.
+#[cfg(feature = "mccs-db")]
+pub(crate) fn is_restorable(desc: &mccs_db::Descriptor) -> bool {
+    let interpretation = match desc.ty {
+        ValueType::Continuous { interpretation } => interpretation,
+        ValueType::NonContinuous { interpretation, .. } => interpretation,
+        ValueType::Unknown | ValueType::Table { .. } => return false,
+    };
+
+    desc.access == Access::ReadWrite
+        && match interpretation {
+            ValueInterpretation::Continuous | ValueInterpretation::NonContinuous => true,
+            ValueInterpretation::NonZeroWrite | ValueInterpretation::VcpVersion => false,
+        }
+}
+
+impl Display {
+    /// Save the current values of the display's features.
+    ///
+    /// When `features` is `None`, every readable and writable feature in the
+    /// display's MCCS database is saved and any that fail to read are
+    /// skipped. Otherwise all of the requested features must be read
+    /// successfully.
+    pub fn snapshot(&mut self, features: Option<&[FeatureCode]>) -> Result<Profile, Error> {
+        trace!("Display::snapshot({:?})", features);
+
+        let mut profile = Profile::new(&self.info);
+        match features {
+            Some(features) =>
+                for &code in features {
+                    let value = self.get_vcp_feature(code)?;
+                    profile.values.insert(code, value.value());
+                },
+            None =>
+                for code in self.restorable_features() {
+                    match self.get_vcp_feature(code) {
+                        Ok(value) => {
+                            profile.values.insert(code, value.value());
+                        },
+                        Err(e) => warn!("Failed to snapshot feature 0x{:02x} of {}: {}", code, self.info, e),
+                    }
+                },
+        }
+
+        Ok(profile)
+    }
+
+    #[cfg(feature = "mccs-db")]
+    fn restorable_features(&self) -> Vec<FeatureCode> {
+        self.info
+            .features()
+            .filter(|desc| is_restorable(desc))
+            .map(|desc| desc.code)
+            .collect()
+    }
+
+    #[cfg(not(feature = "mccs-db"))]
+    fn restorable_features(&self) -> Vec<FeatureCode> {
+        Vec::new()
+    }
+
+    /// Restore the values saved in a profile.
+    ///
+    /// The values are written as a single `VcpTransaction`, so a failure
+    /// part-way through will roll back any values that were already changed.
+    pub fn apply(&mut self, profile: &Profile) -> Result<(), TransactionError> {
+        trace!("Display::apply({}:{})", profile.backend, profile.id);
+
+        if !profile.matches(&self.info) {
+            warn!(
+                "Applying profile from {}:{} to a different model: {}",
+                profile.backend, profile.id, self.info
+            );
+        }
+
+        profile.transaction().apply_to(self)
+    }
+}
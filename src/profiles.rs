@@ -0,0 +1,165 @@
+//! Declarative profiles that assign feature values to displays by query.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use ddc_hi::{profiles::{FeatureValue, ProfileConfig, ProfileRule}, Query};
+//!
+//! let config = ProfileConfig {
+//!     rules: vec![ProfileRule {
+//!         query: Query::ModelName("DELL U2718Q".into()),
+//!         features: vec![(0x10, FeatureValue::Value(70)), (0x60, FeatureValue::Named("HDMI 1".into()))]
+//!             .into_iter()
+//!             .collect(),
+//!     }],
+//! };
+//! for (info, error) in config.apply().failures() {
+//!     eprintln!("{}: {}", info, error);
+//! }
+//! ```
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{Display, DisplayInfo, Error, FeatureCode, Query, TransactionError, VcpTransaction},
+    log::{trace, warn},
+    mccs_db::Access,
+    std::collections::BTreeMap,
+};
+
+/// A set of rules describing which feature values each display should have.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ProfileConfig {
+    /// The rules, applied in order so that later rules take precedence.
+    pub rules: Vec<ProfileRule>,
+}
+
+/// Feature values to assign to every display matching a query.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ProfileRule {
+    /// The displays this rule applies to.
+    pub query: Query,
+    /// The values to write to each feature.
+    pub features: BTreeMap<FeatureCode, FeatureValue>,
+}
+
+/// A feature value, either raw or by name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum FeatureValue {
+    /// A raw value.
+    Value(u16),
+    /// A non-continuous value named in the display's MCCS database, such as
+    /// "HDMI 1" for the input source (`0x60`).
+    Named(String),
+}
+
+/// The reason a profile could not be applied to a display.
+#[derive(Debug, thiserror::Error)]
+pub enum ProfileError {
+    /// The profile is not valid for the display.
+    #[error("invalid profile: {0}")]
+    Invalid(#[source] Error),
+    /// Writing the feature values failed.
+    #[error(transparent)]
+    Transaction(#[from] TransactionError),
+}
+
+/// The per-display outcome of applying a `ProfileConfig`.
+#[derive(Debug, Default)]
+pub struct ProfileReport {
+    /// Each display with a matching rule, and its result.
+    pub results: Vec<(DisplayInfo, Result<(), ProfileError>)>,
+}
+
+impl ProfileReport {
+    /// Whether the profile was applied to every matching display.
+    pub fn is_success(&self) -> bool {
+        self.results.iter().all(|(_, res)| res.is_ok())
+    }
+
+    /// The displays the profile failed to apply to.
+    pub fn failures(&self) -> impl Iterator<Item = (&DisplayInfo, &ProfileError)> {
+        self.results
+            .iter()
+            .filter_map(|(info, res)| res.as_ref().err().map(|e| (info, e)))
+    }
+}
+
+impl ProfileConfig {
+    /// The feature values assigned to a display by all matching rules.
+    pub fn features_for(&self, info: &DisplayInfo) -> BTreeMap<FeatureCode, FeatureValue> {
+        self.rules
+            .iter()
+            .filter(|rule| rule.query.matches(info))
+            .flat_map(|rule| rule.features.iter().map(|(&code, value)| (code, value.clone())))
+            .collect()
+    }
+
+    /// Validate the assigned values against the display's MCCS database and
+    /// build the transaction that writes them.
+    ///
+    /// When the database is empty, raw values are accepted as-is but named
+    /// values cannot be resolved.
+    pub fn resolve(&self, info: &DisplayInfo) -> Result<VcpTransaction, Error> {
+        let known = info.features().next().is_some();
+        self.features_for(info)
+            .into_iter()
+            .try_fold(VcpTransaction::new(), |tx, (code, value)| {
+                match info.mccs_database.get(code) {
+                    Some(desc) if desc.access == Access::ReadOnly =>
+                        return Err(Error::InvalidArgument(format!("feature 0x{:02x} is read-only", code))),
+                    None if known =>
+                        return Err(Error::InvalidArgument(format!(
+                            "feature 0x{:02x} is not supported by {}",
+                            code, info
+                        ))),
+                    _ => (),
+                }
+
+                let value = match value {
+                    FeatureValue::Value(value) => value,
+                    FeatureValue::Named(ref name) => info.value_by_name(code, name).ok_or_else(|| {
+                        Error::InvalidArgument(format!("unknown value {:?} for feature 0x{:02x}", name, code))
+                    })?,
+                };
+                Ok(tx.set(code, value))
+            })
+    }
+
+    /// Apply the profile to a display, if any rule matches it.
+    ///
+    /// The capabilities are read first so that values can be validated and
+    /// resolved by name.
+    pub fn apply_to(&self, display: &mut Display) -> Result<(), ProfileError> {
+        trace!("ProfileConfig::apply_to({})", display.info);
+
+        if let Err(e) = display.update_capabilities() {
+            warn!("Failed to read capabilities of {}: {}", display.info, e);
+        }
+        let tx = self.resolve(&display.info).map_err(ProfileError::Invalid)?;
+        tx.apply_to(display).map_err(From::from)
+    }
+
+    /// Apply the profile to every enumerated display matched by a rule.
+    ///
+    /// Queries are matched against the information available immediately
+    /// after enumeration, which does not include the capabilities string.
+    pub fn apply(&self) -> ProfileReport {
+        trace!("ProfileConfig::apply");
+
+        let results = Display::enumerate()
+            .into_iter()
+            .filter(|display| self.rules.iter().any(|rule| rule.query.matches(&display.info)))
+            .map(|mut display| {
+                let res = self.apply_to(&mut display);
+                (display.info, res)
+            })
+            .collect();
+
+        ProfileReport { results }
+    }
+}
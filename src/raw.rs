@@ -0,0 +1,131 @@
+use {
+    crate::{Delays, Error, Handle},
+    ddc::{
+        commands::{Command, CommandResult},
+        ErrorCode,
+    },
+    log::trace,
+};
+
+/// The maximum length of a raw command's payload, excluding the opcode.
+pub const RAW_PAYLOAD_MAX_LEN: usize = 35;
+
+struct RawCommand<'a, R> {
+    op: u8,
+    payload: &'a [u8],
+    _reply: std::marker::PhantomData<R>,
+}
+
+impl<'a, R: CommandResult> Command for RawCommand<'a, R> {
+    type Ok = R;
+
+    const DELAY_COMMAND_MS: u64 = 50;
+    const DELAY_RESPONSE_MS: u64 = 40;
+    const MAX_LEN: usize = 1 + RAW_PAYLOAD_MAX_LEN;
+    const MIN_LEN: usize = 1;
+
+    fn len(&self) -> usize {
+        1 + self.payload.len()
+    }
+
+    fn encode(&self, data: &mut [u8]) -> Result<usize, ErrorCode> {
+        if data.len() < self.len() {
+            return Err(ErrorCode::InvalidLength)
+        }
+
+        data[0] = self.op;
+        data[1..self.len()].copy_from_slice(self.payload);
+
+        Ok(self.len())
+    }
+}
+
+struct RawReply(Vec<u8>);
+
+impl CommandResult for RawReply {
+    const MAX_LEN: usize = 36;
+
+    fn decode(data: &[u8]) -> Result<Self, ErrorCode> {
+        Ok(RawReply(data.to_owned()))
+    }
+}
+
+impl Handle {
+    /// Send an arbitrary DDC/CI command and read its reply.
+    ///
+    /// The returned data excludes the packet header and checksum, so it begins
+    /// with the reply opcode. This is not supported by the WinAPI backend.
+    ///
+    /// Nothing about the command is validated, so sending unknown opcodes to a
+    /// display may have unexpected effects.
+    pub fn raw_ddc(&mut self, op: u8, payload: &[u8]) -> Result<Vec<u8>, Error> {
+        trace!("Handle::raw_ddc(0x{:02x}, {:?})", op, payload);
+
+        self.execute_with(Self::raw_command(op, payload)?, &Delays::SPEC)
+            .map(|RawReply(reply)| reply)
+    }
+
+    /// Send an arbitrary DDC/CI command that has no reply.
+    ///
+    /// See `raw_ddc` for caveats.
+    pub fn raw_ddc_write(&mut self, op: u8, payload: &[u8]) -> Result<(), Error> {
+        trace!("Handle::raw_ddc_write(0x{:02x}, {:?})", op, payload);
+
+        self.execute_with(Self::raw_command::<()>(op, payload)?, &Delays::SPEC)
+    }
+
+    /// Send an arbitrary DDC/CI command that has no reply, from a
+    /// non-standard source address.
+    ///
+    /// Hosts normally send commands from source address `0x51`, but some
+    /// manufacturers' commands are only accepted from another address, such as
+    /// `0x50`. This is only supported by the i2c-dev backend. See `raw_ddc` for
+    /// caveats.
+    pub fn raw_ddc_write_from(&mut self, source: u8, op: u8, payload: &[u8]) -> Result<(), Error> {
+        trace!(
+            "Handle::raw_ddc_write_from(0x{:02x}, 0x{:02x}, {:?})",
+            source,
+            op,
+            payload
+        );
+
+        Self::raw_command::<()>(op, payload)?;
+        match *self {
+            #[cfg(feature = "has-ddc-i2c")]
+            Handle::I2cDevice(ref mut i2c) => {
+                use {crate::BackendError, std::io::Write};
+
+                let mut packet = vec![source, 0x80 | (1 + payload.len()) as u8, op];
+                packet.extend_from_slice(payload);
+                packet.push(
+                    packet
+                        .iter()
+                        .fold((ddc::I2C_ADDRESS_DDC_CI as u8) << 1, |sum, &b| sum ^ b),
+                );
+
+                let i2c = i2c.inner_mut();
+                i2c.smbus_set_slave_address(ddc::I2C_ADDRESS_DDC_CI, false)
+                    .and_then(|()| i2c.write_all(&packet))
+                    .map_err(|e| BackendError::I2cDeviceError(ddc_i2c::Error::I2c(e)).into())
+            },
+            #[allow(unreachable_patterns)]
+            _ => Err(Error::UnsupportedOp),
+        }
+    }
+
+    fn raw_command<R>(op: u8, payload: &[u8]) -> Result<RawCommand<'_, R>, Error> {
+        if payload.len() > RAW_PAYLOAD_MAX_LEN {
+            return Err(Error::InvalidArgument(format!(
+                "raw payload is {} bytes, the maximum is {}",
+                payload.len(),
+                RAW_PAYLOAD_MAX_LEN
+            )))
+        }
+
+        Ok(RawCommand {
+            op,
+            payload,
+            _reply: Default::default(),
+        })
+    }
+}
@@ -0,0 +1,79 @@
+use {
+    crate::{Ddc, Display, Edid, Error, ErrorKind, FeatureCode, Operation},
+    log::{debug, trace},
+    std::{
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+/// How long to wait between probes while a display is not responding.
+const READY_PROBE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Features read to find out whether a display responds to DDC/CI: the VCP
+/// version and luminance, which nearly every display supports.
+const DDC_CI_PROBES: [FeatureCode; 2] = [0xdf, 0x10];
+
+impl Display {
+    /// Wait until the display responds to DDC/CI commands.
+    ///
+    /// Displays often ignore commands for a second or two after waking up or
+    /// switching inputs. This probes the display by reading its VCP version
+    /// (`0xdf`) until it replies or `timeout` elapses, in which case the error
+    /// from the last probe is returned. Probes are paced and retried like any
+    /// other read, so they don't disturb the timing of other commands on the
+    /// same bus.
+    pub fn wait_ready(&mut self, timeout: Duration) -> Result<(), Error> {
+        trace!("Display::wait_ready({:?})", timeout);
+
+        let start = Instant::now();
+        loop {
+            let e = match self.get_vcp_feature(0xdf) {
+                Ok(_) => return Ok(()),
+                // the display replied, even if it didn't like the question
+                Err(ref e) if e.kind() == ErrorKind::UnsupportedFeature => return Ok(()),
+                Err(e) => e,
+            };
+            if !e.is_transient() || start.elapsed() + READY_PROBE_INTERVAL > timeout {
+                return Err(e)
+            }
+
+            debug!("Waiting for {} to respond: {}", self.info, e);
+            thread::sleep(READY_PROBE_INTERVAL);
+        }
+    }
+
+    /// Guess whether DDC/CI is enabled in the display's on-screen menu.
+    ///
+    /// Many displays ship with DDC/CI disabled, and then acknowledge their
+    /// EDID being read but reply to every command with a null message. This
+    /// returns `Some(false)` for such a display, `Some(true)` if it replies
+    /// to commands, and `None` if it can't be told, such as when the display
+    /// isn't reachable at all or the backend can't read the EDID.
+    pub fn ddc_ci_enabled(&mut self) -> Option<bool> {
+        trace!("Display::ddc_ci_enabled");
+
+        for code in DDC_CI_PROBES {
+            match self.get_vcp_feature(code) {
+                Ok(_) => return Some(true),
+                // the display replied, even if it didn't like the question
+                Err(ref e) if e.kind() == ErrorKind::UnsupportedFeature => return Some(true),
+                Err(ref e) if e.kind() == ErrorKind::Nak =>
+                    debug!("{} did not reply to 0x{:02x}: {}", self.info, code, e),
+                Err(e) => {
+                    debug!("Can't tell whether DDC/CI is enabled on {}: {}", self.info, e);
+                    return None
+                },
+            }
+        }
+
+        // only null messages, but is anything there?
+        if !self.supports(Operation::ReadEdid) {
+            return None
+        }
+        self.read_edid(0, &mut [0u8; 1])
+            .map_err(|e| debug!("Failed to read the EDID of {}: {}", self.info, e))
+            .ok()
+            .map(|_| false)
+    }
+}
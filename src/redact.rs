@@ -0,0 +1,122 @@
+#[cfg(feature = "mccs-db")]
+use crate::DisplayReport;
+use {
+    crate::{CapabilitiesInfo, DisplayInfo},
+    std::{
+        collections::hash_map::{DefaultHasher, RandomState},
+        hash::{BuildHasher, Hasher},
+        sync::OnceLock,
+    },
+};
+
+/// How identifying values are hashed.
+#[derive(Copy, Clone)]
+enum Key<'a> {
+    /// A key chosen at random once per process.
+    Process,
+    /// A key provided by the caller.
+    Secret(&'a [u8]),
+}
+
+impl Key<'_> {
+    fn hash(self, data: &[u8]) -> u64 {
+        static PROCESS: OnceLock<RandomState> = OnceLock::new();
+
+        let mut hasher = match self {
+            Key::Process => PROCESS.get_or_init(RandomState::new).build_hasher(),
+            Key::Secret(key) => {
+                let mut hasher = DefaultHasher::new();
+                hasher.write_usize(key.len());
+                hasher.write(key);
+                hasher
+            },
+        };
+        hasher.write(data);
+        hasher.finish()
+    }
+
+    fn serial(self, serial: Option<u32>) -> Option<u32> {
+        serial.map(|serial| self.hash(&serial.to_le_bytes()) as u32)
+    }
+
+    fn serial_number(self, serial: Option<&str>) -> Option<String> {
+        serial.map(|serial| format!("{:016x}", self.hash(serial.as_bytes())))
+    }
+
+    fn edid(self, edid: Option<&[u8]>) -> Option<Vec<u8>> {
+        edid.map(|edid| self.hash(edid).to_be_bytes().to_vec())
+    }
+}
+
+/// Unrecognized capabilities entries may carry anything, including serial
+/// numbers, so they are dropped.
+fn redact_capabilities(caps: Option<&CapabilitiesInfo>) -> Option<CapabilitiesInfo> {
+    caps.map(|caps| CapabilitiesInfo {
+        unknown_tags: Vec::new(),
+        ..caps.clone()
+    })
+}
+
+impl DisplayInfo {
+    /// A copy with the identifying serial numbers and EDID replaced by hashes
+    /// of them.
+    ///
+    /// Use this before logging or uploading information about a display. The
+    /// EDID is replaced by an 8-byte hash, so it can no longer be parsed, and
+    /// unrecognized capabilities entries are dropped. The hashes are keyed
+    /// with a random key chosen once per process, so redacted values can only
+    /// be correlated within the same process. Use `redacted_with` to correlate
+    /// them across runs.
+    pub fn redacted(&self) -> Self {
+        self.redact(Key::Process)
+    }
+
+    /// A copy redacted as with `redacted`, but hashed with `key` instead of a
+    /// random key.
+    ///
+    /// Redacting with the same key gives the same hashes, so displays can be
+    /// correlated across runs and machines. The key must be kept secret, as
+    /// anyone who knows it can recover serial numbers by hashing every
+    /// possible value. The hashes are only stable for a given Rust toolchain.
+    pub fn redacted_with(&self, key: &[u8]) -> Self {
+        self.redact(Key::Secret(key))
+    }
+
+    fn redact(&self, key: Key) -> Self {
+        DisplayInfo {
+            serial: key.serial(self.serial),
+            serial_number: key.serial_number(self.serial_number.as_deref()),
+            edid_data: key.edid(self.edid_data.as_deref()),
+            capabilities: redact_capabilities(self.capabilities.as_ref()),
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(feature = "mccs-db")]
+impl DisplayReport {
+    /// A copy with the identifying serial numbers and EDID replaced by hashes
+    /// of them, as with `DisplayInfo::redacted`.
+    ///
+    /// The raw capabilities string is omitted, as it may contain the same
+    /// identifying information.
+    pub fn redacted(&self) -> Self {
+        self.redact(Key::Process)
+    }
+
+    /// A copy redacted as with `redacted`, but hashed with `key`, as with
+    /// `DisplayInfo::redacted_with`.
+    pub fn redacted_with(&self, key: &[u8]) -> Self {
+        self.redact(Key::Secret(key))
+    }
+
+    fn redact(&self, key: Key) -> Self {
+        DisplayReport {
+            serial: key.serial(self.serial),
+            serial_number: key.serial_number(self.serial_number.as_deref()),
+            edid: key.edid(self.edid.as_deref()),
+            capabilities: None,
+            ..self.clone()
+        }
+    }
+}
@@ -0,0 +1,206 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{Backend, Ddc, Display, Error, FeatureCode, ManufactureDate},
+    log::{debug, trace},
+    mccs_db::{Access, Descriptor},
+    std::collections::BTreeMap,
+};
+
+/// A complete description of a display, its capabilities and current
+/// settings, as produced by `Display::report`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DisplayReport {
+    /// Identifies the backend or driver used to communicate with the display.
+    pub backend: Backend,
+    /// A unique identifier for the display, format is specific to the backend.
+    pub id: String,
+    /// A three-character identifier of the manufacturer of the display.
+    pub manufacturer_id: Option<String>,
+    /// A number that identifies the product model.
+    pub model_id: Option<u16>,
+    /// The version and revision of the product.
+    pub version: Option<(u8, u8)>,
+    /// Serial number of the device.
+    pub serial: Option<u32>,
+    /// Year the display was manufactured.
+    pub manufacture_year: Option<u8>,
+    /// Week the display was manufactured.
+    pub manufacture_week: Option<u8>,
+    /// When the display was manufactured, decoded from the raw year and
+    /// week.
+    pub manufacture_date: Option<ManufactureDate>,
+    /// The model name of the display.
+    pub model_name: Option<String>,
+    /// Human-readable serial number of the device.
+    pub serial_number: Option<String>,
+    /// Raw EDID data provided by the display.
+    pub edid: Option<Vec<u8>>,
+    /// The MCCS version as a major and minor number.
+    pub mccs_version: Option<(u8, u8)>,
+    /// The raw capabilities string.
+    pub capabilities: Option<String>,
+    /// Why the capabilities could not be read or parsed.
+    pub capabilities_error: Option<String>,
+    /// The features described by the display's MCCS database.
+    pub features: Vec<FeatureReport>,
+}
+
+/// A feature of a display and its current value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FeatureReport {
+    /// The VCP code of the feature.
+    pub code: FeatureCode,
+    /// The name of the feature.
+    pub name: Option<String>,
+    /// A description of the feature.
+    pub description: Option<String>,
+    /// Whether the feature can be read.
+    pub readable: bool,
+    /// Whether the feature can be written.
+    pub writable: bool,
+    /// The named values of a non-continuous feature.
+    pub values: BTreeMap<u8, Option<String>>,
+    /// The current value, if the feature is readable.
+    pub value: Option<u16>,
+    /// The maximum value reported alongside the current value.
+    pub maximum: Option<u16>,
+    /// A human-readable rendering of the current value.
+    pub formatted: Option<String>,
+    /// Why the current value could not be read.
+    pub error: Option<String>,
+}
+
+/// The current state of a feature, as returned by `Display::feature_values`.
+#[derive(Clone, Debug)]
+pub struct FeatureState {
+    /// The current value of the feature.
+    pub value: u16,
+    /// The maximum value reported alongside the current value.
+    pub maximum: u16,
+    /// The database entry describing the feature.
+    pub descriptor: Descriptor,
+}
+
+impl Display {
+    /// Read the current value of every readable feature in the display's
+    /// MCCS database.
+    ///
+    /// Features that fail to read are left out of the result.
+    pub fn feature_values(&mut self) -> BTreeMap<FeatureCode, FeatureState> {
+        trace!("Display::feature_values");
+
+        let descriptors: Vec<_> = self
+            .info
+            .features()
+            .filter(|desc| self.info.is_readable(desc.code))
+            .cloned()
+            .collect();
+
+        let mut values = BTreeMap::new();
+        for descriptor in descriptors {
+            let code = descriptor.code;
+            match self.get_vcp_feature(code) {
+                Ok(value) => {
+                    values.insert(code, FeatureState {
+                        value: value.value(),
+                        maximum: value.maximum(),
+                        descriptor,
+                    });
+                },
+                Err(e) => debug!("Failed to read feature 0x{:02x} of {}: {}", code, self.info, e),
+            }
+        }
+
+        values
+    }
+
+    /// Gather everything known about the display into a single report.
+    ///
+    /// This reads the capabilities string, updating the display info from it,
+    /// and the current value of every readable feature. Failures are recorded
+    /// in the report rather than aborting it.
+    pub fn report(&mut self) -> DisplayReport {
+        trace!("Display::report");
+
+        let (capabilities, capabilities_error) = match self.read_capabilities() {
+            Ok(caps) => (Some(caps), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+
+        let descriptors: Vec<_> = self
+            .info
+            .features()
+            .map(|desc| {
+                (
+                    desc.code,
+                    desc.name.clone(),
+                    desc.description.clone(),
+                    desc.access,
+                    match desc.ty {
+                        mccs_db::ValueType::NonContinuous { ref values, .. } => values.clone(),
+                        _ => Default::default(),
+                    },
+                )
+            })
+            .collect();
+
+        let mut features = Vec::with_capacity(descriptors.len());
+        for (code, name, description, access, values) in descriptors {
+            let readable = self.info.is_readable(code);
+            let mut feature = FeatureReport {
+                code,
+                name,
+                description,
+                readable,
+                writable: access != Access::ReadOnly,
+                values,
+                value: None,
+                maximum: None,
+                formatted: None,
+                error: None,
+            };
+            if readable {
+                match self.get_vcp_feature(code) {
+                    Ok(value) => {
+                        feature.value = Some(value.value());
+                        feature.maximum = Some(value.maximum());
+                        feature.formatted = Some(self.info.format_value(code, &value));
+                    },
+                    Err(e) => feature.error = Some(e.inner().to_string()),
+                }
+            }
+            features.push(feature);
+        }
+
+        let info = &self.info;
+        DisplayReport {
+            backend: info.backend,
+            id: info.id.clone(),
+            manufacturer_id: info.manufacturer_id.clone(),
+            model_id: info.model_id,
+            version: info.version,
+            serial: info.serial,
+            manufacture_year: info.manufacture_year,
+            manufacture_week: info.manufacture_week,
+            manufacture_date: info.manufacture_date(),
+            model_name: info.model_name.clone(),
+            serial_number: info.serial_number.clone(),
+            edid: info.edid_data.clone(),
+            mccs_version: info.mccs_version.map(|v| (v.major, v.minor)),
+            capabilities,
+            capabilities_error,
+            features,
+        }
+    }
+
+    fn read_capabilities(&mut self) -> Result<String, Error> {
+        let raw = self.capabilities_string()?;
+        let caps = mccs_caps::parse_capabilities(&raw).map_err(|e| self.context(Error::CapabilitiesParseError(e)))?;
+        self.fill_capabilities(&caps);
+
+        Ok(String::from_utf8_lossy(&raw).into_owned())
+    }
+}
@@ -0,0 +1,55 @@
+use {
+    crate::{Ddc, Display, Error, FeatureCode},
+    log::trace,
+};
+
+/// An explicit acknowledgement that an irreversible operation should be
+/// performed.
+///
+/// Required by the factory reset helpers so that they can't be invoked by
+/// accident.
+#[derive(Copy, Clone, Debug)]
+pub struct Confirm(());
+
+impl Confirm {
+    /// Confirm that the operation's effects can't be undone.
+    pub fn irreversible() -> Self {
+        Confirm(())
+    }
+}
+
+impl Display {
+    /// Restore all factory defaults (`0x04`).
+    pub fn restore_factory_defaults(&mut self, confirm: Confirm) -> Result<(), Error> {
+        self.restore_factory(0x04, confirm)
+    }
+
+    /// Restore the factory luminance and contrast (`0x05`).
+    pub fn restore_factory_luminance_contrast(&mut self, confirm: Confirm) -> Result<(), Error> {
+        self.restore_factory(0x05, confirm)
+    }
+
+    /// Restore the factory geometry (`0x06`).
+    pub fn restore_factory_geometry(&mut self, confirm: Confirm) -> Result<(), Error> {
+        self.restore_factory(0x06, confirm)
+    }
+
+    /// Restore the factory color defaults (`0x08`).
+    pub fn restore_factory_color(&mut self, confirm: Confirm) -> Result<(), Error> {
+        self.restore_factory(0x08, confirm)
+    }
+
+    /// Restore the factory TV defaults (`0x0a`).
+    pub fn restore_factory_tv_defaults(&mut self, confirm: Confirm) -> Result<(), Error> {
+        self.restore_factory(0x0a, confirm)
+    }
+
+    fn restore_factory(&mut self, code: FeatureCode, _: Confirm) -> Result<(), Error> {
+        trace!("Display::restore_factory(0x{:02x})", code);
+
+        self.set_vcp_feature(code, 1)?;
+        self.cache.clear();
+
+        Ok(())
+    }
+}
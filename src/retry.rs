@@ -0,0 +1,158 @@
+use {
+    crate::{bus, metrics, Display, Error, ErrorKind, FeatureCode, Operation},
+    log::debug,
+    std::{
+        cmp, thread,
+        time::{Duration, Instant},
+    },
+};
+
+/// Controls how failed DDC/CI operations on a `Display` are retried.
+///
+/// Each retry waits for an exponentially increasing backoff, starting at
+/// `initial_backoff` and doubling up to `max_backoff`. By default only
+/// transient errors (see `Error::is_transient`) are retried.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    retry_on: Option<Vec<ErrorKind>>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy of up to three attempts with a 50ms initial backoff.
+    pub fn new() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(1),
+            retry_on: None,
+        }
+    }
+
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self::new().max_attempts(1)
+    }
+
+    /// The total number of attempts made, including the first.
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = cmp::max(attempts, 1);
+        self
+    }
+
+    /// The backoff before the first retry, and the limit it may double up to.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = cmp::max(initial, max);
+        self
+    }
+
+    /// Retry only errors of the given kinds, instead of all transient errors.
+    pub fn retry_on(mut self, kinds: Vec<ErrorKind>) -> Self {
+        self.retry_on = Some(kinds);
+        self
+    }
+
+    /// Whether an error should be retried.
+    pub fn should_retry(&self, error: &Error) -> bool {
+        match self.retry_on {
+            Some(ref kinds) => kinds.contains(&error.kind()),
+            None => error.is_transient(),
+        }
+    }
+
+    /// The backoff to wait after the given failed attempt, counting from zero.
+    pub fn backoff_for(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .checked_mul(1u32.checked_shl(attempt).unwrap_or(u32::MAX))
+            .unwrap_or(self.max_backoff);
+        cmp::min(backoff, self.max_backoff)
+    }
+
+    pub(crate) fn run<T, F: FnMut() -> Result<T, Error>>(&self, mut f: F) -> Result<T, Error> {
+        let mut attempt = 0;
+        loop {
+            match f() {
+                Err(e) if attempt + 1 < self.max_attempts && self.should_retry(&e) => {
+                    let backoff = self.backoff_for(attempt);
+                    debug!(
+                        "Retrying after attempt {} failed, waiting {:?}: {}",
+                        attempt + 1,
+                        backoff,
+                        e
+                    );
+                    thread::sleep(backoff);
+                },
+                res => return res,
+            }
+            attempt += 1;
+        }
+    }
+}
+
+impl<H> Display<H> {
+    /// The policy used to retry failed operations.
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    /// Change the policy used to retry failed operations.
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry = policy;
+    }
+
+    /// Run an operation against the handle, retrying it according to the
+    /// retry policy and attaching the display's identity to any error.
+    pub(crate) fn run<T, F: FnMut(&mut H) -> Result<T, Error>>(
+        &mut self,
+        op: Operation,
+        code: Option<FeatureCode>,
+        f: F,
+    ) -> Result<T, Error> {
+        self.blacklist.check(op, code).map_err(|e| self.context(e))?;
+        bus::wait(&self.bus);
+
+        let res = self.attempt(op, code, f);
+        bus::busy(&self.bus, self.delays.operation_delay(op, res.is_err()));
+        res
+    }
+
+    /// Run an operation as `run` does, but without checking the blacklist,
+    /// waiting for the bus to be ready, or marking it busy afterwards, for
+    /// batches of operations that are paced by the handle itself.
+    pub(crate) fn attempt<T, F: FnMut(&mut H) -> Result<T, Error>>(
+        &mut self,
+        op: Operation,
+        code: Option<FeatureCode>,
+        mut f: F,
+    ) -> Result<T, Error> {
+        #[cfg(feature = "tracing")]
+        let span = crate::spans::transaction(&self.info, op, code);
+
+        let start = Instant::now();
+        let mut attempts = 0;
+        let handle = &mut self.handle;
+        let res = self.retry.run(|| {
+            attempts += 1;
+            f(handle)
+        });
+        metrics::record_operation(op, attempts, res.is_err());
+        if let Some(metrics) = self.metrics.as_mut() {
+            metrics.record(op, start.elapsed(), attempts, res.is_err());
+        }
+        self.blacklist.record(op, code, &res);
+        #[cfg(feature = "tracing")]
+        crate::spans::record(&span, attempts, &res);
+
+        res.map_err(|e| self.context(e))
+    }
+}
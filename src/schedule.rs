@@ -0,0 +1,222 @@
+//! Apply different profiles depending on the time of day.
+//!
+//! Displays may be switched off, replaced, or adjusted from their menus while
+//! the host sleeps. A gap between polls much longer than the polling interval
+//! is treated as a resume from sleep and reapplies the active profile.
+//!
+//! The scheduler is driven only by polling. This crate has no hotplug or
+//! system event subsystem, so displays connected between polls are not
+//! configured until the next entry takes effect or a resume is detected.
+//! Hotplug and session events from elsewhere, such as udev or logind, must be
+//! forwarded with `Scheduler::reapply`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use {
+//!     ddc_hi::{
+//!         profiles::{FeatureValue, ProfileConfig, ProfileRule},
+//!         schedule::{Schedule, ScheduleEntry, Scheduler},
+//!         Query,
+//!     },
+//!     std::time::Duration,
+//! };
+//!
+//! let brightness = |value| ProfileConfig {
+//!     rules: vec![ProfileRule {
+//!         query: Query::Any,
+//!         features: vec![(0x10, FeatureValue::Value(value))].into_iter().collect(),
+//!     }],
+//! };
+//! let schedule = Schedule {
+//!     utc_offset: 0,
+//!     entries: vec![
+//!         ScheduleEntry { hour: 7, minute: 0, profile: brightness(80) },
+//!         ScheduleEntry { hour: 21, minute: 30, profile: brightness(20) },
+//!     ],
+//! };
+//! Scheduler::new(&schedule, Duration::from_secs(60)).run();
+//! ```
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::profiles::{ProfileConfig, ProfileReport},
+    log::{debug, info, trace, warn},
+    std::{
+        thread,
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    },
+};
+
+const MINUTES_PER_DAY: i64 = 24 * 60;
+
+/// Profiles to apply at different times of day.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Schedule {
+    /// The offset of local time from UTC, in minutes.
+    pub utc_offset: i32,
+    /// The profiles and when they take effect.
+    pub entries: Vec<ScheduleEntry>,
+}
+
+/// A profile that takes effect at a time of day.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScheduleEntry {
+    /// The local hour, from 0 to 23.
+    pub hour: u8,
+    /// The minute of the hour.
+    pub minute: u8,
+    /// The profile to apply.
+    pub profile: ProfileConfig,
+}
+
+impl ScheduleEntry {
+    fn minute_of_day(&self) -> i64 {
+        self.hour as i64 * 60 + self.minute as i64
+    }
+}
+
+impl Schedule {
+    /// The index of the entry in effect at `time`.
+    ///
+    /// This is the entry that most recently took effect, wrapping around to
+    /// the last one of the previous day.
+    pub fn active_at(&self, time: SystemTime) -> Option<usize> {
+        let minutes = match time.duration_since(UNIX_EPOCH) {
+            Ok(since) => (since.as_secs() / 60) as i64,
+            Err(e) => -((e.duration().as_secs() / 60) as i64),
+        };
+        let now = (minutes + self.utc_offset as i64).rem_euclid(MINUTES_PER_DAY);
+
+        let latest = |filter: &dyn Fn(i64) -> bool| {
+            self.entries
+                .iter()
+                .enumerate()
+                .filter(|(_, entry)| filter(entry.minute_of_day()))
+                .max_by_key(|(_, entry)| entry.minute_of_day())
+                .map(|(i, _)| i)
+        };
+        latest(&|at| at <= now).or_else(|| latest(&|_| true))
+    }
+}
+
+/// Applies the profiles of a `Schedule` as they come into effect.
+pub struct Scheduler<'a> {
+    schedule: &'a Schedule,
+    interval: Duration,
+    active: Option<usize>,
+    last_poll: Option<SystemTime>,
+}
+
+impl<'a> Scheduler<'a> {
+    /// Create a scheduler that is polled every `interval`.
+    pub fn new(schedule: &'a Schedule, interval: Duration) -> Self {
+        Scheduler {
+            schedule,
+            interval,
+            active: None,
+            last_poll: None,
+        }
+    }
+
+    /// Apply the active profile if it changed or the host appears to have
+    /// resumed since the last poll.
+    pub fn poll(&mut self) -> Option<ProfileReport> {
+        trace!("Scheduler::poll");
+
+        let now = SystemTime::now();
+        let resumed = self
+            .last_poll
+            .and_then(|last| now.duration_since(last).ok())
+            .is_some_and(|elapsed| elapsed > self.interval * 2);
+        self.last_poll = Some(now);
+
+        let active = self.schedule.active_at(now);
+        if active != self.active {
+            info!("Schedule entry {:?} is now active", active);
+        } else if resumed {
+            debug!("Reapplying schedule entry {:?} after resume", active);
+        } else {
+            return None
+        }
+
+        self.active = active;
+        self.reapply()
+    }
+
+    /// Apply the active profile again, for example after a session unlock.
+    pub fn reapply(&mut self) -> Option<ProfileReport> {
+        trace!("Scheduler::reapply");
+
+        let entry = &self.schedule.entries[self.active?];
+        let report = entry.profile.apply();
+        for (info, e) in report.failures() {
+            warn!("Failed to apply scheduled profile to {}: {}", info, e);
+        }
+        Some(report)
+    }
+
+    /// Poll forever.
+    pub fn run(&mut self) -> ! {
+        loop {
+            self.poll();
+            thread::sleep(self.interval);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(utc_offset: i32, times: &[(u8, u8)]) -> Schedule {
+        Schedule {
+            utc_offset,
+            entries: times
+                .iter()
+                .map(|&(hour, minute)| ScheduleEntry {
+                    hour,
+                    minute,
+                    profile: Default::default(),
+                })
+                .collect(),
+        }
+    }
+
+    fn at(hour: u64, minute: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(((hour * 60) + minute) * 60)
+    }
+
+    #[test]
+    fn active_at_latest_entry() {
+        let schedule = schedule(0, &[(21, 30), (7, 0)]);
+        assert_eq!(schedule.active_at(at(7, 0)), Some(1));
+        assert_eq!(schedule.active_at(at(12, 0)), Some(1));
+        assert_eq!(schedule.active_at(at(21, 30)), Some(0));
+        assert_eq!(schedule.active_at(at(23, 59)), Some(0));
+    }
+
+    #[test]
+    fn active_at_wraps_around() {
+        let schedule = schedule(0, &[(7, 0), (21, 30)]);
+        assert_eq!(schedule.active_at(at(0, 0)), Some(1));
+        assert_eq!(schedule.active_at(at(6, 59)), Some(1));
+    }
+
+    #[test]
+    fn active_at_negative_offset() {
+        // 03:00 UTC is 22:00 the previous day at UTC-5
+        let schedule = schedule(-5 * 60, &[(7, 0), (21, 30)]);
+        assert_eq!(schedule.active_at(at(3, 0)), Some(1));
+        assert_eq!(schedule.active_at(at(12, 0)), Some(0));
+        assert_eq!(schedule.active_at(at(11, 59)), Some(1));
+    }
+
+    #[test]
+    fn active_at_empty() {
+        assert_eq!(schedule(0, &[]).active_at(at(12, 0)), None);
+    }
+}
@@ -0,0 +1,54 @@
+use {
+    crate::{Display, FeatureCode},
+    log::debug,
+    std::{collections::BTreeMap, time::Duration},
+};
+
+/// Features that commonly make a display stop responding for a while after
+/// they are written, and how long to wait for it to recover.
+pub(crate) fn default_settle_times() -> BTreeMap<FeatureCode, Duration> {
+    [
+        // input source
+        (0x60, Duration::from_secs(3)),
+        // power mode
+        (0xd6, Duration::from_secs(5)),
+    ]
+    .into_iter()
+    .collect()
+}
+
+impl Display {
+    /// How long the display may take to respond again after a feature is
+    /// written.
+    ///
+    /// Plain writes don't wait. The input switch helpers and verified
+    /// transactions wait up to this long for the display to respond after
+    /// writing a feature with a settle time, such as the input source
+    /// (`0x60`), so that subsequent commands and verification reads don't
+    /// spuriously fail.
+    pub fn settle_time(&self, code: FeatureCode) -> Option<Duration> {
+        self.settle_times.get(&code).copied()
+    }
+
+    /// Change how long to wait for the display after a feature is written,
+    /// or don't wait at all with `None`.
+    pub fn set_settle_time(&mut self, code: FeatureCode, settle: Option<Duration>) {
+        match settle {
+            Some(settle) => self.settle_times.insert(code, settle),
+            None => self.settle_times.remove(&code),
+        };
+    }
+
+    /// Wait for the display to recover after writing a feature, for up to
+    /// its settle time.
+    ///
+    /// The write itself succeeded, so a display that doesn't come back in
+    /// time is left for later commands to report.
+    pub fn settle(&mut self, code: FeatureCode) {
+        if let Some(settle) = self.settle_time(code) {
+            if let Err(e) = self.wait_ready(settle) {
+                debug!("{} did not settle after writing 0x{:02x}: {}", self.info, code, e);
+            }
+        }
+    }
+}
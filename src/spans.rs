@@ -0,0 +1,27 @@
+use {
+    crate::{DisplayInfo, Error, FeatureCode, Operation},
+    tracing::{field, span::EnteredSpan},
+};
+
+/// Enter a span covering a single DDC/CI transaction with a display.
+pub(crate) fn transaction(info: &DisplayInfo, op: Operation, code: Option<FeatureCode>) -> EnteredSpan {
+    tracing::debug_span!(
+        "ddc",
+        backend = %info.backend,
+        display = %info.id,
+        op = ?op,
+        code = code,
+        attempts = field::Empty,
+        outcome = field::Empty,
+    )
+    .entered()
+}
+
+/// Record how a transaction went on its span.
+pub(crate) fn record<T>(span: &EnteredSpan, attempts: u32, res: &Result<T, Error>) {
+    span.record("attempts", attempts);
+    match *res {
+        Ok(_) => span.record("outcome", "ok"),
+        Err(ref e) => span.record("outcome", field::debug(e.kind())),
+    };
+}
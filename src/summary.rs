@@ -0,0 +1,80 @@
+use {crate::DisplayInfo, std::fmt};
+
+/// A short one-line description of a display, as returned by
+/// `DisplayInfo::summary`.
+///
+/// It borrows the display info and is only formatted when displayed.
+#[derive(Copy, Clone, Debug)]
+pub struct DisplaySummary<'a> {
+    info: &'a DisplayInfo,
+}
+
+impl<'a> DisplaySummary<'a> {
+    /// The manufacturer, by name where it is known.
+    fn manufacturer(&self) -> Option<&'a str> {
+        #[cfg(feature = "pnp-ids")]
+        if let Some(name) = self.info.manufacturer_name() {
+            return name.split_whitespace().next()
+        }
+
+        self.info.manufacturer_id.as_deref()
+    }
+
+    /// The output the display is connected to, without the DRM card.
+    fn output(&self) -> Option<&'a str> {
+        match self.info.drm_connector.as_deref() {
+            Some(connector) => Some(connector.split_once('-').map_or(connector, |(_, output)| output)),
+            None => self.info.adapter.as_ref().and_then(|adapter| adapter.source.as_deref()),
+        }
+    }
+}
+
+impl fmt::Display for DisplaySummary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let info = self.info;
+        let mut sep = "";
+
+        if let Some(manufacturer) = self.manufacturer() {
+            write!(f, "{}", manufacturer)?;
+            sep = " ";
+        }
+
+        if let Some(ref model) = info.model_name {
+            write!(f, "{}{}", sep, model)?;
+            sep = " ";
+        } else if let Some(model) = info.model_id {
+            write!(f, "{}{}", sep, model)?;
+            sep = " ";
+        }
+
+        if let Some(ref serial) = info.serial_number {
+            write!(f, "{}#{}", sep, serial)?;
+            sep = " ";
+        } else if let Some(serial) = info.serial.filter(|&serial| serial != 0) {
+            write!(f, "{}#{}", sep, serial)?;
+            sep = " ";
+        }
+
+        if sep.is_empty() {
+            write!(f, "{}", info.id)?;
+        }
+        write!(f, " via {}", info.backend)?;
+
+        if let Some(output) = self.output() {
+            write!(f, " ({})", output)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl DisplayInfo {
+    /// A short one-line description of the display, such as
+    /// `Dell U2720Q #ABC123 via i2c-dev (DP-1)`.
+    ///
+    /// Parts that aren't known are left out, and the display's `id` stands in
+    /// if nothing identifies the model.
+    pub fn summary(&self) -> DisplaySummary<'_> {
+        DisplaySummary { info: self }
+    }
+}
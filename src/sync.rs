@@ -0,0 +1,124 @@
+//! Keep a feature of several displays in step with another display.
+//!
+//! Values are scaled by each display's maximum, so displays with different
+//! ranges end up at the same relative setting.
+//!
+//! # Example
+//!
+//! Continuously mirror the luminance of one display onto the others using a
+//! `FeatureWatcher`:
+//!
+//! ```rust,no_run
+//! use {
+//!     ddc_hi::{sync::Mirror, Display, FeatureWatcher, Query},
+//!     std::time::Duration,
+//! };
+//!
+//! let source = Query::Id("1".into());
+//! let mut mirror = Mirror::new(&Query::Id("2".into()), 0x10);
+//! let watcher = FeatureWatcher::open(
+//!     move || {
+//!         Display::enumerate()
+//!             .into_iter()
+//!             .find(|display| source.matches(&display.info))
+//!     },
+//!     vec![0x10],
+//!     Duration::from_secs(1),
+//! );
+//! for change in watcher.events() {
+//!     mirror.apply(change.new);
+//! }
+//! ```
+
+use {
+    crate::{broadcast::BroadcastReport, Ddc, Display, Error, FeatureCode, Query, VcpValue},
+    log::{debug, trace},
+};
+
+/// Applies a feature value to a set of displays, scaled by each display's
+/// maximum.
+pub struct Mirror {
+    code: FeatureCode,
+    targets: Vec<(Display, Option<u16>)>,
+}
+
+impl Mirror {
+    /// Enumerate the displays that match `targets`.
+    pub fn new(targets: &Query, code: FeatureCode) -> Self {
+        Mirror {
+            code,
+            targets: Display::enumerate()
+                .into_iter()
+                .filter(|display| targets.matches(&display.info))
+                .map(|display| (display, None))
+                .collect(),
+        }
+    }
+
+    /// The displays values are applied to.
+    pub fn targets(&self) -> impl Iterator<Item = &Display> {
+        self.targets.iter().map(|(display, _)| display)
+    }
+
+    /// Apply a value read from the source display to every target.
+    pub fn apply(&mut self, value: VcpValue) -> BroadcastReport {
+        trace!("Mirror::apply(0x{:02x}, {})", self.code, value.value());
+
+        let code = self.code;
+        let results = self
+            .targets
+            .iter_mut()
+            .map(|(display, maximum)| {
+                let res = target_maximum(display, code, maximum)
+                    .and_then(|maximum| display.set_vcp_feature(code, scale(value, maximum)));
+                (display.info.clone(), res)
+            })
+            .collect();
+
+        BroadcastReport { results }
+    }
+}
+
+/// The maximum value of a target's feature, read once and remembered.
+fn target_maximum(display: &mut Display, code: FeatureCode, maximum: &mut Option<u16>) -> Result<u16, Error> {
+    if let Some(maximum) = *maximum {
+        return Ok(maximum)
+    }
+
+    let value = display.get_vcp_feature(code)?;
+    *maximum = Some(value.maximum());
+    Ok(value.maximum())
+}
+
+fn scale(value: VcpValue, maximum: u16) -> u16 {
+    match value.maximum() {
+        0 => value.value().min(maximum),
+        source =>
+            ((value.value() as u32 * maximum as u32 + source as u32 / 2) / source as u32).min(maximum as u32) as u16,
+    }
+}
+
+/// Read a feature from the first display that matches `source` and apply it
+/// to every other display that matches `targets`.
+pub fn mirror_feature(source: &Query, targets: &Query, code: FeatureCode) -> Result<BroadcastReport, Error> {
+    trace!("sync::mirror_feature({:?}, {:?}, 0x{:02x})", source, targets, code);
+
+    let mut displays = Display::enumerate();
+    let index = displays
+        .iter()
+        .position(|display| source.matches(&display.info))
+        .ok_or_else(|| Error::InvalidArgument(format!("no display matches {:?}", source)))?;
+    let mut source = displays.remove(index);
+    let value = source.get_vcp_feature(code)?;
+    debug!("Mirroring 0x{:02x} = {} from {}", code, value.value(), source.info);
+
+    let mut mirror = Mirror {
+        code,
+        targets: displays
+            .into_iter()
+            .filter(|display| targets.matches(&display.info))
+            .map(|display| (display, None))
+            .collect(),
+    };
+    Ok(mirror.apply(value))
+}
@@ -0,0 +1,209 @@
+use {
+    crate::{Display, Error, FeatureCode, Operation},
+    ddc::{
+        commands::{TableRead, TableWrite},
+        ErrorCode,
+    },
+    log::{info, trace},
+};
+
+/// The maximum amount of data transferred by a single table command.
+const TABLE_CHUNK_LEN: usize = 32;
+
+/// The largest table addressable by the 16-bit offsets of table commands.
+const TABLE_MAX_LEN: usize = 0x10000;
+
+impl Display {
+    /// Read the full contents of a table feature.
+    ///
+    /// The table is read one chunk at a time, retrying any chunk that fails
+    /// or arrives corrupted according to the retry policy. `progress` is called
+    /// with the number of bytes read so far after each chunk. At most 64 KiB
+    /// are read, the most that table offsets can address.
+    pub fn read_table(
+        &mut self,
+        code: FeatureCode,
+        mut progress: Option<&mut dyn FnMut(usize)>,
+    ) -> Result<Vec<u8>, Error> {
+        trace!("Display::read_table(0x{:02x})", code);
+
+        self.read_table_chunks(code, 0, TABLE_MAX_LEN, &mut |read| {
+            if let Some(progress) = progress.as_mut() {
+                progress(read);
+            }
+            true
+        })
+    }
+
+    /// Read up to `len` bytes of a table feature, starting at `offset`.
+    ///
+    /// Like `read_table`, each chunk is retried on its own, and reading stops at
+    /// offset `0xffff`. A failed transfer can be resumed by reading the
+    /// remaining range again. `progress` is
+    /// called with the number of bytes read so far after each chunk, and may
+    /// return `false` to stop early, in which case only those bytes are
+    /// returned.
+    pub fn read_table_range(
+        &mut self,
+        code: FeatureCode,
+        offset: u16,
+        len: usize,
+        mut progress: Option<&mut dyn FnMut(usize) -> bool>,
+    ) -> Result<Vec<u8>, Error> {
+        trace!("Display::read_table_range(0x{:02x}, {}, {})", code, offset, len);
+
+        self.read_table_chunks(code, offset, len, &mut |read| match progress.as_mut() {
+            Some(progress) => progress(read),
+            None => true,
+        })
+    }
+
+    fn read_table_chunks(
+        &mut self,
+        code: FeatureCode,
+        start: u16,
+        len: usize,
+        progress: &mut dyn FnMut(usize) -> bool,
+    ) -> Result<Vec<u8>, Error> {
+        let delays = self.delays;
+        read_chunks(
+            start,
+            len,
+            &mut |offset| {
+                self.run(Operation::Command, Some(code), |handle| {
+                    let chunk = handle.execute_with(TableRead::new(code, offset), &delays)?;
+                    match chunk.offset == offset {
+                        true => Ok(chunk.bytes().to_owned()),
+                        false => Err(Error::InvalidResponse(ErrorCode::InvalidOffset)),
+                    }
+                })
+            },
+            progress,
+        )
+    }
+
+    /// Write the full contents of a table feature.
+    ///
+    /// The data is written one chunk at a time, retrying any chunk that
+    /// fails according to the retry policy. `progress` is called with the number of bytes written
+    /// so far and the total length after each chunk. Tables larger than 64 KiB can't be
+    /// addressed and are rejected before anything is written.
+    pub fn write_table(
+        &mut self,
+        code: FeatureCode,
+        data: &[u8],
+        mut progress: Option<&mut dyn FnMut(usize, usize)>,
+    ) -> Result<(), Error> {
+        trace!("Display::write_table(0x{:02x}, {} bytes)", code, data.len());
+
+        if data.len() > TABLE_MAX_LEN {
+            return Err(Error::InvalidArgument(format!(
+                "{} bytes is too large for table 0x{:02x}",
+                data.len(),
+                code
+            )))
+        }
+
+        if self.dry_run {
+            info!(
+                "Dry run: not writing {} bytes to table 0x{:02x} of {}",
+                data.len(),
+                code,
+                self.info
+            );
+            return Ok(())
+        }
+
+        let delays = self.delays;
+        for (i, chunk) in data.chunks(TABLE_CHUNK_LEN).enumerate() {
+            let offset = (i * TABLE_CHUNK_LEN) as u16;
+            self.run(Operation::Command, Some(code), |handle| {
+                handle.execute_with(TableWrite::new(code, offset, chunk), &delays)
+            })?;
+            if let Some(progress) = progress.as_mut() {
+                progress(offset as usize + chunk.len(), data.len());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Read up to `len` bytes starting at `start` with `read`, which reads the
+/// chunk at an offset. Reading stops early at an empty chunk, at the end of the
+/// 16-bit offset range, or when `progress` returns `false`.
+fn read_chunks(
+    start: u16,
+    len: usize,
+    read: &mut dyn FnMut(u16) -> Result<Vec<u8>, Error>,
+    progress: &mut dyn FnMut(usize) -> bool,
+) -> Result<Vec<u8>, Error> {
+    let len = len.min(TABLE_MAX_LEN - start as usize);
+    let mut table = Vec::new();
+    while table.len() < len {
+        let chunk = read(start + table.len() as u16)?;
+        if chunk.is_empty() {
+            break
+        }
+
+        table.extend_from_slice(&chunk[..chunk.len().min(len - table.len())]);
+        if !progress(table.len()) {
+            break
+        }
+    }
+
+    Ok(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_from(table: &[u8], start: u16, len: usize) -> Vec<u8> {
+        let mut offsets = Vec::new();
+        let read = read_chunks(
+            start,
+            len,
+            &mut |offset| {
+                offsets.push(offset);
+                let offset = offset as usize;
+                Ok(table[offset.min(table.len())..]
+                    .iter()
+                    .take(TABLE_CHUNK_LEN)
+                    .copied()
+                    .collect())
+            },
+            &mut |_| true,
+        )
+        .unwrap();
+        assert!(offsets.windows(2).all(|w| w[0] < w[1]));
+        read
+    }
+
+    #[test]
+    fn read_chunks_until_empty() {
+        let table: Vec<u8> = (0..100).collect();
+        assert_eq!(read_from(&table, 0, TABLE_MAX_LEN), table);
+        assert_eq!(read_from(&table, 10, 5), &table[10..15]);
+    }
+
+    #[test]
+    fn read_chunks_end_of_offsets() {
+        let table: Vec<u8> = (0..TABLE_MAX_LEN + 100).map(|i| i as u8).collect();
+        assert_eq!(read_from(&table, 0, usize::MAX), &table[..TABLE_MAX_LEN]);
+        assert_eq!(read_from(&table, 0xfff0, 0x100), &table[0xfff0..TABLE_MAX_LEN]);
+    }
+
+    #[test]
+    fn read_chunks_stops_on_progress() {
+        let table = [0u8; 100];
+        let read = read_chunks(
+            0,
+            100,
+            &mut |offset| Ok(table[offset as usize..][..10].to_vec()),
+            &mut |read| read < 30,
+        )
+        .unwrap();
+        assert_eq!(read.len(), 30);
+    }
+}
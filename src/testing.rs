@@ -0,0 +1,248 @@
+//! Displays backed by a recorded snapshot instead of hardware, so that tests
+//! have deterministic display fixtures.
+//!
+//! # Example
+//!
+//! ```rust
+//! use ddc_hi::{testing::Snapshot, Backend, Ddc, Display};
+//!
+//! let snapshot = Snapshot::new().value(0x10, 50, 100);
+//! let mut display = Display::from_snapshot(Backend::I2cDevice, "test".into(), snapshot).unwrap();
+//! display.set_vcp_feature(0x10, 70).unwrap();
+//! assert_eq!(display.get_vcp_feature(0x10).unwrap().value(), 70);
+//! ```
+//!
+//! `Faults` can make a snapshot misbehave like a flaky display, to exercise
+//! retry and error handling.
+
+#[cfg(feature = "mccs-db")]
+use crate::DisplayReport;
+use {
+    crate::{
+        Backend, Ddc, DdcHost, DdcTable, Delays, Display, DisplayInfo, Edid, Error, FeatureCode, Handle, VcpValue,
+    },
+    ddc::ErrorCode,
+    std::{cmp, collections::BTreeMap, io, thread, time::Duration},
+};
+
+fn unsupported() -> Error {
+    Error::InvalidResponse(ErrorCode::Invalid("Unsupported VCP code".into()))
+}
+
+/// Misbehavior injected into the responses of a `Snapshot`.
+///
+/// Faults are chosen by a pseudo-random generator seeded with `seed`, so a
+/// failing test can be reproduced.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Faults {
+    /// Seeds the choice of which operations misbehave.
+    pub seed: u64,
+    /// The fraction of operations that fail as if the display did not
+    /// acknowledge them.
+    pub nak_rate: f64,
+    /// The fraction of capabilities reads that fail with a corrupted reply.
+    pub corrupt_capabilities_rate: f64,
+    /// The fraction of reads after which the value drifts by one step, as
+    /// with a display adjusting itself.
+    pub drift_rate: f64,
+    /// How long every operation takes to respond.
+    pub delay: Duration,
+}
+
+/// The recorded state of a display.
+///
+/// Reads answer from the snapshot and writes update it. Features and tables
+/// that are not present are reported as unsupported, as a display would.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Snapshot {
+    /// Raw EDID data.
+    pub edid: Option<Vec<u8>>,
+    /// The raw capabilities string.
+    pub capabilities: Option<String>,
+    /// The current VCP feature values.
+    pub values: BTreeMap<FeatureCode, VcpValue>,
+    /// The contents of table features.
+    pub tables: BTreeMap<FeatureCode, Vec<u8>>,
+    /// Misbehavior to inject.
+    pub faults: Faults,
+    rng: u64,
+}
+
+impl Snapshot {
+    /// Create an empty snapshot.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add a feature with its current and maximum value.
+    pub fn value(mut self, code: FeatureCode, value: u16, maximum: u16) -> Self {
+        self.values.insert(code, VcpValue {
+            ty: 0,
+            mh: (maximum >> 8) as u8,
+            ml: maximum as u8,
+            sh: (value >> 8) as u8,
+            sl: value as u8,
+        });
+        self
+    }
+
+    /// Inject faults into the snapshot's responses.
+    pub fn faults(mut self, faults: Faults) -> Self {
+        self.rng = faults.seed;
+        self.faults = faults;
+        self
+    }
+
+    /// Whether an event with the given probability happens, using xorshift64*.
+    fn chance(&mut self, probability: f64) -> bool {
+        if probability <= 0.0 {
+            return false
+        }
+
+        // xorshift gets stuck at zero
+        let mut x = match self.rng {
+            0 => 0x9e37_79b9_7f4a_7c15,
+            x => x,
+        };
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.rng = x;
+        let sample = (x.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 11) as f64 / (1u64 << 53) as f64;
+        sample < probability
+    }
+
+    /// Wait out the response delay and possibly fail the operation.
+    fn respond(&mut self) -> Result<(), Error> {
+        if !self.faults.delay.is_zero() {
+            thread::sleep(self.faults.delay);
+        }
+
+        match self.chance(self.faults.nak_rate) {
+            // ddc reports a null message as an invalid length
+            true => Err(Error::InvalidResponse(ErrorCode::InvalidLength)),
+            false => Ok(()),
+        }
+    }
+
+    fn drift(&mut self, code: FeatureCode) {
+        if !self.chance(self.faults.drift_rate) {
+            return
+        }
+
+        let up = self.chance(0.5);
+        if let Some(current) = self.values.get_mut(&code) {
+            let value = match up {
+                true => cmp::min(current.value().saturating_add(1), current.maximum()),
+                false => current.value().saturating_sub(1),
+            };
+            current.sh = (value >> 8) as u8;
+            current.sl = value as u8;
+        }
+    }
+
+    /// Recreate the state of a display from its report.
+    #[cfg(feature = "mccs-db")]
+    pub fn from_report(report: &DisplayReport) -> Self {
+        report.features.iter().fold(
+            Snapshot {
+                edid: report.edid.clone(),
+                capabilities: report.capabilities.clone(),
+                ..Default::default()
+            },
+            |snapshot, feature| match (feature.value, feature.maximum) {
+                (Some(value), Some(maximum)) => snapshot.value(feature.code, value, maximum),
+                _ => snapshot,
+            },
+        )
+    }
+}
+
+impl DdcHost for Snapshot {
+    type Error = Error;
+}
+
+impl Ddc for Snapshot {
+    fn capabilities_string(&mut self) -> Result<Vec<u8>, Self::Error> {
+        self.respond()?;
+        if self.chance(self.faults.corrupt_capabilities_rate) {
+            return Err(Error::InvalidResponse(ErrorCode::InvalidChecksum))
+        }
+
+        self.capabilities
+            .as_ref()
+            .map(|caps| caps.as_bytes().to_vec())
+            .ok_or(Error::UnsupportedOp)
+    }
+
+    fn get_vcp_feature(&mut self, code: FeatureCode) -> Result<VcpValue, Self::Error> {
+        self.respond()?;
+        let value = self.values.get(&code).copied().ok_or_else(unsupported)?;
+        self.drift(code);
+        Ok(value)
+    }
+
+    fn set_vcp_feature(&mut self, code: FeatureCode, value: u16) -> Result<(), Self::Error> {
+        self.respond()?;
+        let current = self.values.get_mut(&code).ok_or_else(unsupported)?;
+        current.sh = (value >> 8) as u8;
+        current.sl = value as u8;
+        Ok(())
+    }
+
+    fn save_current_settings(&mut self) -> Result<(), Self::Error> {
+        self.respond()
+    }
+
+    fn get_timing_report(&mut self) -> Result<ddc::TimingMessage, Self::Error> {
+        Err(Error::UnsupportedOp)
+    }
+}
+
+impl DdcTable for Snapshot {
+    fn table_read(&mut self, code: FeatureCode) -> Result<Vec<u8>, Self::Error> {
+        self.respond()?;
+        self.tables.get(&code).cloned().ok_or_else(unsupported)
+    }
+
+    fn table_write(&mut self, code: FeatureCode, offset: u16, value: &[u8]) -> Result<(), Self::Error> {
+        self.respond()?;
+        let table = self.tables.get_mut(&code).ok_or_else(unsupported)?;
+        let offset = offset as usize;
+        table.resize(cmp::max(table.len(), offset + value.len()), 0);
+        table[offset..offset + value.len()].copy_from_slice(value);
+        Ok(())
+    }
+}
+
+impl Edid for Snapshot {
+    type EdidError = Error;
+
+    fn read_edid(&mut self, offset: u8, data: &mut [u8]) -> Result<usize, Self::EdidError> {
+        let edid = self.edid.as_deref().ok_or(Error::UnsupportedOp)?;
+        let edid = edid.get(offset as usize..).unwrap_or_default();
+        let len = cmp::min(edid.len(), data.len());
+        data[..len].copy_from_slice(&edid[..len]);
+        Ok(len)
+    }
+}
+
+impl Display {
+    /// Create a display that answers from a snapshot.
+    ///
+    /// The display information is parsed from the snapshot's EDID and
+    /// capabilities, and no delays are waited between operations.
+    pub fn from_snapshot(backend: Backend, id: String, snapshot: Snapshot) -> io::Result<Self> {
+        #[cfg(feature = "mccs-db")]
+        let info = DisplayInfo::from_raw(backend, id, snapshot.edid.as_deref(), snapshot.capabilities.as_deref())?;
+        #[cfg(not(feature = "mccs-db"))]
+        let info = match snapshot.edid {
+            Some(ref edid) => DisplayInfo::from_edid(backend, id, edid.clone())?,
+            None => DisplayInfo::new(backend, id),
+        };
+
+        let mut display = Display::from_handle(Handle::Snapshot(snapshot), info);
+        display.set_delays(Delays::scaled(0));
+        Ok(display)
+    }
+}
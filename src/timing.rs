@@ -0,0 +1,54 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use {
+    crate::{Ddc, Display, Error, TimingMessage},
+    log::trace,
+};
+
+/// A decoded display timing report.
+///
+/// The packet checksum is validated by the backend while the report is read,
+/// so only well-formed reports are decoded.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimingReport {
+    /// Horizontal sync frequency in kHz.
+    pub horizontal_frequency_khz: f64,
+    /// Vertical sync frequency in Hz.
+    pub vertical_frequency_hz: f64,
+    /// The horizontal sync polarity is positive.
+    pub horizontal_sync_positive: bool,
+    /// The vertical sync polarity is positive.
+    pub vertical_sync_positive: bool,
+    /// The sync frequency is out of the display's supported range.
+    pub out_of_range: bool,
+    /// The display reports an unstable sync count.
+    pub unstable: bool,
+    /// The raw timing status byte.
+    pub status: u8,
+}
+
+impl From<TimingMessage> for TimingReport {
+    fn from(msg: TimingMessage) -> Self {
+        TimingReport {
+            // reported in units of 10Hz
+            horizontal_frequency_khz: msg.horizontal_frequency as f64 / 100.0,
+            // reported in units of 0.01Hz
+            vertical_frequency_hz: msg.vertical_frequency as f64 / 100.0,
+            horizontal_sync_positive: msg.timing_status & 0x02 != 0,
+            vertical_sync_positive: msg.timing_status & 0x01 != 0,
+            out_of_range: msg.timing_status & 0x80 != 0,
+            unstable: msg.timing_status & 0x40 != 0,
+            status: msg.timing_status,
+        }
+    }
+}
+
+impl Display {
+    /// Request and decode the display's timing report.
+    pub fn timing(&mut self) -> Result<TimingReport, Error> {
+        trace!("Display::timing");
+
+        self.get_timing_report().map(From::from)
+    }
+}
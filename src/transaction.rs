@@ -0,0 +1,202 @@
+use {
+    crate::{Ddc, Display, Error, FeatureCode},
+    log::{trace, warn},
+    std::collections::BTreeSet,
+};
+
+/// A staged set of VCP feature writes that are applied together.
+///
+/// Writes are applied in the order they were staged. When rollback is
+/// enabled, the prior value of each feature is read before it is written,
+/// and if any write fails the features that were already changed are
+/// restored in reverse order.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use ddc_hi::{Display, VcpTransaction};
+///
+/// for mut display in Display::enumerate() {
+///     VcpTransaction::new()
+///         .set(0x10, 70)
+///         .set(0x12, 50)
+///         .apply(&mut display)
+///         .unwrap();
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct VcpTransaction {
+    writes: Vec<(FeatureCode, u16)>,
+    verify: bool,
+    rollback: bool,
+}
+
+impl Default for VcpTransaction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VcpTransaction {
+    /// Create an empty transaction with verification and rollback enabled.
+    pub fn new() -> Self {
+        VcpTransaction {
+            writes: Vec::new(),
+            verify: true,
+            rollback: true,
+        }
+    }
+
+    /// Stage a write of `value` to the feature `code`.
+    pub fn set(mut self, code: FeatureCode, value: u16) -> Self {
+        self.writes.push((code, value));
+        self
+    }
+
+    /// Whether each write is read back and compared against the written value.
+    pub fn verify(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
+
+    /// Whether already applied writes are reverted when a later one fails.
+    pub fn rollback(mut self, rollback: bool) -> Self {
+        self.rollback = rollback;
+        self
+    }
+
+    /// The writes staged in this transaction, in application order.
+    pub fn writes(&self) -> &[(FeatureCode, u16)] {
+        &self.writes
+    }
+
+    /// Apply the staged writes to a display.
+    ///
+    /// Every feature is assumed to be readable; use `apply_to` to avoid
+    /// reading back write-only and momentary features.
+    pub fn apply<D: Ddc<Error = Error>>(&self, ddc: &mut D) -> Result<(), TransactionError> {
+        trace!("VcpTransaction::apply({:?})", self.writes);
+
+        self.apply_with(ddc, |_| true, |_, _| ())
+    }
+
+    /// Apply the staged writes to a display, consulting its MCCS database.
+    ///
+    /// Features the database describes as write-only or momentary are
+    /// neither verified nor rolled back, since they can't be read. Nothing is
+    /// verified or rolled back while the display is in dry-run mode.
+    pub fn apply_to(&self, display: &mut Display) -> Result<(), TransactionError> {
+        trace!("VcpTransaction::apply_to({}, {:?})", display.info, self.writes);
+
+        // nothing is actually written during a dry run, so don't verify it
+        let readable: BTreeSet<_> = self
+            .writes
+            .iter()
+            .map(|&(code, _)| code)
+            .filter(|&code| !display.dry_run() && display.info.is_readable(code))
+            .collect();
+        self.apply_with(display, |code| readable.contains(&code), Display::settle)
+    }
+
+    fn apply_with<D, R, S>(&self, ddc: &mut D, readable: R, settle: S) -> Result<(), TransactionError>
+    where
+        D: Ddc<Error = Error>,
+        R: Fn(FeatureCode) -> bool,
+        S: Fn(&mut D, FeatureCode),
+    {
+        let mut applied = Vec::with_capacity(self.writes.len());
+        for &(code, value) in &self.writes {
+            let readable = readable(code);
+            let res = self.read_prior(ddc, code, readable).and_then(|prior| {
+                // the write may have taken effect even if it then fails verification
+                applied.push((code, prior));
+                self.apply_one(ddc, code, value, readable, &settle)
+            });
+
+            if let Err(error) = res {
+                let rollback_errors = if self.rollback {
+                    Self::revert(ddc, &applied)
+                } else {
+                    Vec::new()
+                };
+
+                return Err(TransactionError {
+                    code,
+                    error,
+                    rolled_back: self.rollback,
+                    rollback_errors,
+                })
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read_prior<D: Ddc<Error = Error>>(
+        &self,
+        ddc: &mut D,
+        code: FeatureCode,
+        readable: bool,
+    ) -> Result<Option<u16>, Error> {
+        match self.rollback && readable {
+            true => ddc.get_vcp_feature(code).map(|value| Some(value.value())),
+            false => Ok(None),
+        }
+    }
+
+    fn apply_one<D: Ddc<Error = Error>, S: Fn(&mut D, FeatureCode)>(
+        &self,
+        ddc: &mut D,
+        code: FeatureCode,
+        value: u16,
+        readable: bool,
+        settle: &S,
+    ) -> Result<(), Error> {
+        ddc.set_vcp_feature(code, value)?;
+
+        if self.verify && readable {
+            // the display may not answer the verification read straight away
+            settle(ddc, code);
+            let actual = ddc.get_vcp_feature(code)?.value();
+            if actual != value {
+                return Err(Error::VerificationFailed {
+                    code,
+                    expected: value,
+                    actual,
+                })
+            }
+        }
+
+        Ok(())
+    }
+
+    fn revert<D: Ddc<Error = Error>>(ddc: &mut D, applied: &[(FeatureCode, Option<u16>)]) -> Vec<(FeatureCode, Error)> {
+        applied
+            .iter()
+            .rev()
+            .filter_map(|&(code, prior)| prior.map(|prior| (code, prior)))
+            .filter_map(|(code, prior)| match ddc.set_vcp_feature(code, prior) {
+                Ok(()) => None,
+                Err(e) => {
+                    warn!("Failed to roll back feature 0x{:02x}: {}", code, e);
+                    Some((code, e))
+                },
+            })
+            .collect()
+    }
+}
+
+/// The error returned when a `VcpTransaction` could not be fully applied.
+#[derive(Debug, thiserror::Error)]
+#[error("failed to apply feature 0x{code:02x}: {error}")]
+pub struct TransactionError {
+    /// The feature whose write failed.
+    pub code: FeatureCode,
+    /// The reason the write failed.
+    #[source]
+    pub error: Error,
+    /// Whether the previously applied writes were rolled back.
+    pub rolled_back: bool,
+    /// Any features that could not be restored during rollback.
+    pub rollback_errors: Vec<(FeatureCode, Error)>,
+}
@@ -0,0 +1,143 @@
+use {
+    crate::{Display, DisplayInfo, Edid, Error, Operation},
+    log::{debug, trace},
+};
+
+/// The part of the EDID that identifies a display: the header, manufacturer,
+/// product code, serial number and date of manufacture.
+const EDID_IDENTITY_LEN: usize = 0x12;
+
+/// How much information `Display::update` gathers about a display.
+///
+/// Each level includes everything gathered by the levels before it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UpdateLevel {
+    /// The EDID, if the backend is able to read it.
+    Identity,
+    /// The capabilities string, with the `mccs-db` feature.
+    Capabilities,
+    /// The VCP version (`0xdf`), which the capabilities string may omit.
+    Full,
+}
+
+/// The result of `Display::verify_identity`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum IdentityCheck {
+    /// The display still matches its EDID.
+    Unchanged,
+    /// A different display is now behind the handle.
+    Changed,
+    /// The EDID is unknown or can't be read by the backend, so the display
+    /// can't be checked.
+    Unknown,
+}
+
+impl Display {
+    /// Fill in missing display info, up to the specified level.
+    ///
+    /// Only information that is still missing is requested from the display,
+    /// and nothing already known is replaced, so repeated calls are cheap and
+    /// a higher level can follow a lower one.
+    pub fn update(&mut self, level: UpdateLevel) -> Result<(), Error> {
+        trace!("Display::update({:?})", level);
+
+        if self.info.edid_data.is_none() && self.supports(Operation::ReadEdid) {
+            self.update_edid()?;
+        }
+
+        #[cfg(feature = "mccs-db")]
+        if level >= UpdateLevel::Capabilities {
+            self.update_capabilities()?;
+        }
+
+        if level >= UpdateLevel::Full {
+            self.update_from_ddc()?;
+        }
+
+        Ok(())
+    }
+
+    /// Forget the information read from the display, so that it is read
+    /// again by the next update.
+    ///
+    /// This clears the EDID and everything parsed from it, unless the
+    /// backend can't read it again, along with the capabilities, VCP version
+    /// and cached values. Information provided by the operating system, such
+    /// as the adapter, is kept.
+    pub fn invalidate(&mut self) {
+        trace!("Display::invalidate");
+
+        let info = &mut self.info;
+        if self.handle.supports(Operation::ReadEdid) {
+            info.manufacturer_id = None;
+            info.model_id = None;
+            info.version = None;
+            info.serial = None;
+            info.manufacture_year = None;
+            info.manufacture_week = None;
+            info.model_name = None;
+            info.serial_number = None;
+            info.edid_data = None;
+        }
+        info.mccs_version = None;
+        info.capabilities = None;
+        #[cfg(feature = "mccs-db")]
+        {
+            info.mccs_database = Default::default();
+            self.filled_caps = false;
+            self.partial_caps = None;
+        }
+        self.cache.clear();
+    }
+
+    /// Read the display's information again, such as after a firmware
+    /// update or when a KVM switch has swapped the display behind the handle.
+    pub fn refresh(&mut self) -> Result<(), Error> {
+        trace!("Display::refresh");
+
+        self.invalidate();
+        self.update(UpdateLevel::Full)
+    }
+
+    /// Check whether the display behind the handle is still the one
+    /// described by its EDID.
+    ///
+    /// A KVM switch or dock may swap the display connected to a port without
+    /// the handle changing. Only the identifying part of the EDID is read
+    /// again; after a change, use `Display::refresh` to describe the new
+    /// display.
+    pub fn verify_identity(&mut self) -> Result<IdentityCheck, Error> {
+        trace!("Display::verify_identity");
+
+        let known = match self.info.edid_data {
+            Some(ref edid) if edid.len() >= EDID_IDENTITY_LEN && self.supports(Operation::ReadEdid) =>
+                edid[..EDID_IDENTITY_LEN].to_vec(),
+            _ => return Ok(IdentityCheck::Unknown),
+        };
+
+        let mut edid = [0u8; EDID_IDENTITY_LEN];
+        if self.read_edid(0, &mut edid)? < EDID_IDENTITY_LEN {
+            return Err(self.context(Error::InvalidResponse(ddc::ErrorCode::InvalidLength)))
+        }
+        Ok(match edid[..] == known[..] {
+            true => IdentityCheck::Unchanged,
+            false => {
+                debug!("The display behind {} has changed", self.info);
+                IdentityCheck::Changed
+            },
+        })
+    }
+
+    fn update_edid(&mut self) -> Result<(), Error> {
+        let mut edid = vec![0u8; 0x100];
+        let len = self.read_edid(0, &mut edid)?;
+        edid.truncate(len);
+
+        match DisplayInfo::from_edid(self.info.backend, self.info.id.clone(), edid) {
+            Ok(info) => self.info.update_from(&info),
+            Err(e) => debug!("Failed to parse the EDID of {}: {}", self.info, e),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+//! Manufacturer-specific VCP features.
+//!
+//! Manufacturers commonly implement controls in the MCCS manufacturer range
+//! (`0xe0` to `0xff`) that are not described by the capabilities string. The
+//! features listed here are selected by the manufacturer ID from the display's
+//! EDID, and are not guaranteed to be supported by every model.
+//!
+//! Vendor features are written with raw DDC/CI commands, as some of them must
+//! be sent from a non-standard source address that the usual VCP commands
+//! cannot use. This module therefore requires the `unsafe-raw` feature.
+
+use {
+    crate::{Display, DisplayInfo, Error, FeatureCode, Operation},
+    log::{info, trace},
+};
+
+/// The source address that hosts normally send DDC/CI commands from.
+pub const SOURCE_ADDRESS_HOST: u8 = 0x51;
+
+/// The opcode of the DDC/CI Set VCP Feature command.
+const SET_VCP_FEATURE: u8 = 0x03;
+
+/// A display manufacturer with known vendor-specific features.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Vendor {
+    /// Dell Inc.
+    Dell,
+    /// LG Electronics
+    Lg,
+}
+
+/// A vendor-specific VCP feature.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VendorFeature {
+    /// The VCP code of the feature.
+    pub code: FeatureCode,
+    /// A short name for the feature.
+    pub name: &'static str,
+    /// What the feature controls.
+    pub description: &'static str,
+    /// The DDC/CI source address that commands for this feature must be sent
+    /// from.
+    pub source_address: u8,
+}
+
+/// Dell vendor-specific features.
+pub mod dell {
+    use crate::FeatureCode;
+
+    /// Switches the USB upstream port of the built-in KVM.
+    pub const KVM_SWITCH: FeatureCode = 0xe5;
+}
+
+/// LG vendor-specific features.
+pub mod lg {
+    use crate::FeatureCode;
+
+    /// The source address that LG displays expect vendor commands from.
+    pub const SOURCE_ADDRESS: u8 = 0x50;
+
+    /// An alternate input source selector that many LG models use in place
+    /// of `0x60`, with vendor-specific values.
+    pub const INPUT_SOURCE: FeatureCode = 0xf4;
+}
+
+const DELL_FEATURES: &[VendorFeature] = &[VendorFeature {
+    code: dell::KVM_SWITCH,
+    name: "KVM Switch",
+    description: "Switches the USB upstream port of the built-in KVM",
+    source_address: SOURCE_ADDRESS_HOST,
+}];
+
+const LG_FEATURES: &[VendorFeature] = &[VendorFeature {
+    code: lg::INPUT_SOURCE,
+    name: "Input Source",
+    description: "Alternate input source selector with vendor-specific values",
+    source_address: lg::SOURCE_ADDRESS,
+}];
+
+impl Vendor {
+    /// Identify a vendor from the three-character EDID manufacturer ID.
+    pub fn from_manufacturer_id(id: &str) -> Option<Self> {
+        Some(match id {
+            "DEL" => Vendor::Dell,
+            "GSM" => Vendor::Lg,
+            _ => return None,
+        })
+    }
+
+    /// The known vendor-specific features.
+    pub fn features(&self) -> &'static [VendorFeature] {
+        match *self {
+            Vendor::Dell => DELL_FEATURES,
+            Vendor::Lg => LG_FEATURES,
+        }
+    }
+
+    /// Look up a vendor-specific feature by its code.
+    pub fn feature(&self, code: FeatureCode) -> Option<&'static VendorFeature> {
+        self.features().iter().find(|f| f.code == code)
+    }
+}
+
+impl DisplayInfo {
+    /// The manufacturer of the display, if it has known vendor-specific
+    /// features.
+    pub fn vendor(&self) -> Option<Vendor> {
+        self.manufacturer_id.as_deref().and_then(Vendor::from_manufacturer_id)
+    }
+}
+
+impl Display {
+    /// Write a vendor-specific feature of the display.
+    ///
+    /// The command is sent from the source address the vendor expects, which
+    /// is only possible with the i2c-dev backend when it differs from the
+    /// usual host address.
+    pub fn set_vendor_feature(&mut self, code: FeatureCode, value: u16) -> Result<(), Error> {
+        trace!("Display::set_vendor_feature(0x{:02x}, {})", code, value);
+
+        let feature = match self.info.vendor().and_then(|vendor| vendor.feature(code)) {
+            Some(feature) => *feature,
+            None =>
+                return Err(Error::InvalidArgument(format!(
+                    "0x{:02x} is not a known vendor feature of {}",
+                    code, self.info
+                ))),
+        };
+
+        if self.dry_run {
+            info!(
+                "Dry run: not setting vendor feature 0x{:02x} of {} to {}",
+                code, self.info, value
+            );
+            return Ok(())
+        }
+
+        self.cache.invalidate(code);
+
+        let payload = [code, (value >> 8) as u8, value as u8];
+        self.run(Operation::Command, Some(code), |handle| match feature.source_address {
+            SOURCE_ADDRESS_HOST => handle.raw_ddc_write(SET_VCP_FEATURE, &payload),
+            source => handle.raw_ddc_write_from(source, SET_VCP_FEATURE, &payload),
+        })
+    }
+}
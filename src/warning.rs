@@ -0,0 +1,73 @@
+use {
+    crate::{Backend, Display, EnumerationError},
+    log::warn,
+    std::{
+        cell::RefCell,
+        collections::BTreeSet,
+        sync::{Arc, Mutex, PoisonError, RwLock},
+    },
+};
+
+/// A callback that receives the warnings raised while enumerating displays.
+pub type WarningSink = Arc<dyn Fn(&str) + Send + Sync>;
+
+static SINK: RwLock<Option<WarningSink>> = RwLock::new(None);
+static SEEN: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+thread_local! {
+    static FAILURES: RefCell<Option<Vec<EnumerationError>>> = const { RefCell::new(None) };
+}
+
+/// Start a new enumeration, forgetting the warnings already reported.
+pub(crate) fn reset() {
+    SEEN.lock().unwrap_or_else(PoisonError::into_inner).clear();
+}
+
+/// Log a warning and report it to the sink, unless it was already reported
+/// during this enumeration.
+pub(crate) fn warning(message: String) {
+    warn!("{}", message);
+
+    let sink = SINK.read().unwrap_or_else(PoisonError::into_inner).clone();
+    if let Some(sink) = sink {
+        if SEEN
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(message.clone())
+        {
+            sink(&message);
+        }
+    }
+}
+
+/// Report a display or backend that failed to enumerate.
+pub(crate) fn failure(backend: Backend, message: String) {
+    FAILURES.with(|failures| {
+        if let Some(failures) = failures.borrow_mut().as_mut() {
+            failures.push(EnumerationError {
+                backend,
+                message: message.clone(),
+            });
+        }
+    });
+    warning(message);
+}
+
+/// Run an enumeration, collecting the failures reported on this thread.
+pub(crate) fn collect<T, F: FnOnce() -> T>(f: F) -> (T, Vec<EnumerationError>) {
+    let outer = FAILURES.with(|failures| failures.replace(Some(Vec::new())));
+    let res = f();
+    let collected = FAILURES.with(|failures| failures.replace(outer));
+    (res, collected.unwrap_or_default())
+}
+
+impl Display {
+    /// Receive the warnings raised while enumerating displays, such as a
+    /// backend failing to initialize, in addition to them being logged.
+    ///
+    /// Warnings are reported in the order they occur, and each distinct
+    /// warning only once per enumeration. `None` removes the sink.
+    pub fn set_warning_sink(sink: Option<WarningSink>) {
+        *SINK.write().unwrap_or_else(PoisonError::into_inner) = sink;
+    }
+}
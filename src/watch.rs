@@ -0,0 +1,162 @@
+use {
+    crate::{Ddc, FeatureCode, VcpValue},
+    log::{trace, warn},
+    std::{
+        collections::BTreeMap,
+        fmt,
+        sync::mpsc::{self, Receiver, RecvTimeoutError, Sender},
+        thread::{self, JoinHandle},
+        time::Duration,
+    },
+};
+
+/// A change in a watched feature's value.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FeatureChange {
+    /// The feature that changed.
+    pub code: FeatureCode,
+    /// The value previously observed.
+    pub old: VcpValue,
+    /// The newly observed value.
+    pub new: VcpValue,
+}
+
+/// Polls a set of VCP features on a background thread and reports changes,
+/// such as those made through the display's on-screen menu.
+///
+/// The first poll only records the initial values; events are emitted for
+/// any subsequent change. Read failures are logged and otherwise ignored.
+/// Dropping the watcher stops the thread.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use {ddc_hi::{Display, FeatureWatcher}, std::time::Duration};
+///
+/// let watcher = FeatureWatcher::open(
+///     || Display::enumerate().into_iter().next(),
+///     vec![0x10],
+///     Duration::from_secs(1),
+/// );
+/// for change in watcher.events() {
+///     println!("brightness {} -> {}", change.old.value(), change.new.value());
+/// }
+/// ```
+pub struct FeatureWatcher<D = ()> {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<D>>,
+    events: Receiver<FeatureChange>,
+}
+
+impl<D: Ddc + Send + 'static> FeatureWatcher<D>
+where
+    D::Error: fmt::Display,
+{
+    /// Start watching `codes` on `ddc`, polling every `interval`.
+    ///
+    /// The handle is moved to the polling thread, so it must be `Send`.
+    /// `Handle` isn't on Windows builds with NVAPI support; use `open` to
+    /// open the display on the polling thread instead.
+    pub fn new(mut ddc: D, codes: Vec<FeatureCode>, interval: Duration) -> Self {
+        let (stop, stop_rx) = mpsc::channel();
+        let (events_tx, events) = mpsc::channel();
+
+        let thread = thread::spawn(move || {
+            poll(&mut ddc, &codes, interval, &stop_rx, &events_tx);
+            ddc
+        });
+
+        FeatureWatcher {
+            stop: Some(stop),
+            thread: Some(thread),
+            events,
+        }
+    }
+}
+
+impl FeatureWatcher {
+    /// Start watching `codes` on the display returned by `open`, polling
+    /// every `interval`.
+    ///
+    /// The display is opened, used and dropped on the polling thread, so it
+    /// doesn't need to be `Send`. Nothing is watched if `open` returns
+    /// `None`.
+    pub fn open<F, H>(open: F, codes: Vec<FeatureCode>, interval: Duration) -> Self
+    where
+        F: FnOnce() -> Option<H> + Send + 'static,
+        H: Ddc,
+        H::Error: fmt::Display,
+    {
+        let (stop, stop_rx) = mpsc::channel();
+        let (events_tx, events) = mpsc::channel();
+
+        let thread = thread::spawn(move || match open() {
+            Some(mut ddc) => poll(&mut ddc, &codes, interval, &stop_rx, &events_tx),
+            None => warn!("FeatureWatcher: no display to watch"),
+        });
+
+        FeatureWatcher {
+            stop: Some(stop),
+            thread: Some(thread),
+            events,
+        }
+    }
+}
+
+/// Poll `codes` until stopped or nobody is listening for events.
+fn poll<D: Ddc>(
+    ddc: &mut D,
+    codes: &[FeatureCode],
+    interval: Duration,
+    stop: &Receiver<()>,
+    events: &Sender<FeatureChange>,
+) where
+    D::Error: fmt::Display,
+{
+    let mut values = BTreeMap::new();
+    loop {
+        for &code in codes {
+            let new = match ddc.get_vcp_feature(code) {
+                Ok(value) => value,
+                Err(e) => {
+                    warn!("Failed to poll feature 0x{:02x}: {}", code, e);
+                    continue
+                },
+            };
+
+            match values.insert(code, new) {
+                Some(old) if old.value() != new.value() => {
+                    trace!("FeatureWatcher: 0x{:02x} {} -> {}", code, old.value(), new.value());
+                    if events.send(FeatureChange { code, old, new }).is_err() {
+                        return
+                    }
+                },
+                _ => (),
+            }
+        }
+
+        match stop.recv_timeout(interval) {
+            Err(RecvTimeoutError::Timeout) => (),
+            Ok(()) | Err(RecvTimeoutError::Disconnected) => return,
+        }
+    }
+}
+
+impl<D> FeatureWatcher<D> {
+    /// The channel that change events are delivered to.
+    pub fn events(&self) -> &Receiver<FeatureChange> {
+        &self.events
+    }
+
+    /// Stop polling and return the handle once the thread exits.
+    pub fn stop(mut self) -> thread::Result<D> {
+        drop(self.stop.take());
+        self.thread.take().expect("watcher thread already joined").join()
+    }
+}
+
+impl<D> Drop for FeatureWatcher<D> {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+    }
+}